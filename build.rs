@@ -0,0 +1,208 @@
+use std::env;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+#[path = "src/assets/shader_preprocessor.rs"]
+mod shader_preprocessor;
+
+use shader_preprocessor::{preprocess_shader, PreprocessedShader};
+
+const SHADER_DIRECTORY: &str = "shaders";
+/// The reference GLSL compiler, used to check the shaders when it is installed.
+const GLSL_VALIDATOR: &str = "glslangValidator";
+/// The assets besides the shaders built into the executable: the default duck, so that
+/// it looks right anywhere. The other textures are left out to keep the executable
+/// small; a missing one is drawn as a checkerboard.
+const EMBEDDED_FILES: [&str; 2] = ["meshes/duck.txt", "textures/ducktex.jpg"];
+
+/// Writes the table of assets embedded in the executable and checks its shaders, so
+/// that a broken include or a shader that does not compile fails the build instead of
+/// the first run.
+fn main() {
+    println!("cargo:rerun-if-changed={}", SHADER_DIRECTORY);
+    for file in EMBEDDED_FILES {
+        println!("cargo:rerun-if-changed={}", file);
+    }
+
+    let shader_keys = get_shader_keys();
+    check_shaders(&shader_keys);
+
+    let keys = shader_keys
+        .into_iter()
+        .chain(EMBEDDED_FILES.iter().map(PathBuf::from));
+    write_embedded_assets(keys);
+}
+
+/// Writes `embedded_assets.rs` to `OUT_DIR`, a table from each key to its contents.
+fn write_embedded_assets(keys: impl Iterator<Item = PathBuf>) {
+    let manifest_directory = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap());
+    let mut table = String::from("&[\n");
+    for key in keys {
+        let key = key.to_str().unwrap().replace('\\', "/");
+        let path = manifest_directory.join(&key);
+        table += &format!("    ({:?}, include_bytes!({:?})),\n", key, path);
+    }
+    table += "]\n";
+
+    let output = PathBuf::from(env::var("OUT_DIR").unwrap()).join("embedded_assets.rs");
+    fs::write(output, table).unwrap();
+}
+
+fn check_shaders(keys: &[PathBuf]) {
+    let mut errors = vec![];
+    for key in keys {
+        let content = fs::read_to_string(key).unwrap();
+        if content
+            .lines()
+            .all(|line| !line.trim().starts_with("#version"))
+        {
+            // Files without a version line are only meant to be included.
+            continue;
+        }
+
+        match preprocess_shader(key, &mut |file| {
+            fs::read_to_string(file).map_err(|error| format!("{}: {}", file.display(), error))
+        }) {
+            Ok(shader) => match validate_shader(key, &shader) {
+                Some(shader_errors) => errors.extend(shader_errors),
+                None => errors.extend(check_structure(key, &shader)),
+            },
+            Err(error) => errors.push(error),
+        }
+    }
+
+    if !errors.is_empty() {
+        panic!("invalid shaders:\n{}", errors.join("\n"));
+    }
+}
+
+fn get_shader_keys() -> Vec<PathBuf> {
+    let mut keys: Vec<PathBuf> = fs::read_dir(SHADER_DIRECTORY)
+        .unwrap()
+        .map(|entry| Path::new(SHADER_DIRECTORY).join(entry.unwrap().file_name()))
+        .filter(|key| key.extension().is_some_and(|extension| extension == "glsl"))
+        .collect();
+    keys.sort();
+    keys
+}
+
+/// Compiles the preprocessed shader with `glslangValidator` and returns its errors, or
+/// `None` when the validator is not installed.
+fn validate_shader(key: &Path, shader: &PreprocessedShader) -> Option<Vec<String>> {
+    let stem = key.file_stem().unwrap().to_str().unwrap();
+    let extension = match stem.rsplit('_').next() {
+        Some("vertex") => "vert",
+        Some("fragment") => "frag",
+        Some("compute") => "comp",
+        _ => return Some(vec![format!("{}: unknown shader stage", key.display())]),
+    };
+
+    let directory = PathBuf::from(env::var("OUT_DIR").unwrap()).join(SHADER_DIRECTORY);
+    fs::create_dir_all(&directory).unwrap();
+    let path = directory.join(stem).with_extension(extension);
+    fs::write(&path, &shader.source).unwrap();
+
+    let output = match Command::new(GLSL_VALIDATOR).arg(&path).output() {
+        Ok(output) => output,
+        Err(error) if error.kind() == ErrorKind::NotFound => return None,
+        Err(error) => panic!("cannot run {}: {}", GLSL_VALIDATOR, error),
+    };
+    if output.status.success() {
+        return Some(vec![]);
+    }
+
+    // Errors look like `ERROR: <path>:<line>: message`; the line is mapped back to the
+    // file it came from.
+    let prefix = format!("ERROR: {}:", path.display());
+    let errors = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| {
+            let rest = line.strip_prefix(&prefix)?;
+            let (line_number, message) = rest.split_once(':')?;
+            let location = match line_number
+                .parse()
+                .ok()
+                .and_then(|line_number| shader.get_source_location(line_number))
+            {
+                Some((file, file_line)) => format!("{}:{}", file.display(), file_line),
+                None => format!("{}:{}", key.display(), line_number),
+            };
+            Some(format!("{}:{}", location, message))
+        })
+        .collect::<Vec<_>>();
+    if errors.is_empty() {
+        let log = String::from_utf8_lossy(&output.stdout);
+        return Some(vec![format!("{}: {}", key.display(), log.trim())]);
+    }
+    Some(errors)
+}
+
+/// The fallback when `glslangValidator` is not installed: a cheap structural check that
+/// only finds a missing `#version` line, stray backslashes and unbalanced brackets.
+/// Everything else is left to the driver, which reports it when the program is linked.
+fn check_structure(key: &Path, shader: &PreprocessedShader) -> Vec<String> {
+    let location = |line_number| match shader.get_source_location(line_number) {
+        Some((file, file_line)) => format!("{}:{}", file.display(), file_line),
+        None => format!("{}:{}", key.display(), line_number),
+    };
+
+    let mut errors = vec![];
+    let first_line = shader.source.lines().find(|line| !line.trim().is_empty());
+    if !first_line.is_some_and(|line| line.trim().starts_with("#version")) {
+        errors.push(format!(
+            "{}: the first line must be #version",
+            key.display()
+        ));
+    }
+
+    let mut open: Vec<(char, usize)> = vec![];
+    let mut in_block_comment = false;
+    for (index, line) in shader.source.lines().enumerate() {
+        let line_number = index + 1;
+        if line.trim_end().ends_with('\\') {
+            errors.push(format!("{}: stray backslash", location(line_number)));
+        }
+
+        let mut characters = line.chars().peekable();
+        while let Some(character) = characters.next() {
+            if in_block_comment {
+                if character == '*' && characters.peek() == Some(&'/') {
+                    characters.next();
+                    in_block_comment = false;
+                }
+                continue;
+            }
+            match character {
+                '/' if characters.peek() == Some(&'/') => break,
+                '/' if characters.peek() == Some(&'*') => {
+                    characters.next();
+                    in_block_comment = true;
+                }
+                '(' | '[' | '{' => open.push((character, line_number)),
+                ')' | ']' | '}' => {
+                    let expected = match character {
+                        ')' => '(',
+                        ']' => '[',
+                        _ => '{',
+                    };
+                    match open.pop() {
+                        Some((opening, _)) if opening == expected => {}
+                        _ => errors.push(format!(
+                            "{}: unmatched '{}'",
+                            location(line_number),
+                            character
+                        )),
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    errors.extend(open.into_iter().map(|(character, line_number)| {
+        format!("{}: unclosed '{}'", location(line_number), character)
+    }));
+    errors
+}
@@ -0,0 +1,19 @@
+#version 140
+
+in vec2 v_tex_coords;
+flat in int v_tex_index;
+out vec4 color;
+
+uniform sampler2D tex1;
+uniform sampler2D tex2;
+uniform sampler2D tex3;
+
+void main() {
+    if (v_tex_index == 0) {
+        color = texture(tex1, v_tex_coords);
+    } else if (v_tex_index == 1) {
+        color = texture(tex2, v_tex_coords);
+    } else {
+        color = texture(tex3, v_tex_coords);
+    }
+}
@@ -0,0 +1,17 @@
+#version 140
+
+in vec3 position;
+in vec2 tex_coords;
+in int tex_index;
+out vec2 v_tex_coords;
+flat out int v_tex_index;
+
+uniform mat4 perspective;
+uniform mat4 model;
+uniform mat4 view;
+
+void main() {
+    v_tex_coords = tex_coords;
+    v_tex_index = tex_index;
+    gl_Position = perspective * view * model * vec4(position, 1.0);
+}
@@ -0,0 +1,53 @@
+// Looks up the color seen along a ray from inside the pool, which is the cube from
+// -1 to 1 with the walls in tex1, the sky in tex2 and the sand in tex3.
+
+uniform sampler2D tex1;
+uniform sampler2D tex2;
+uniform sampler2D tex3;
+
+vec3 intersect_ray(vec3 p, vec3 v) {
+    vec3 t_m = (vec3(-1, -1, -1) - p) / v;
+    vec3 t_p = (vec3(1, 1, 1) - p) / v;
+    vec3 t = max(t_m, t_p);
+    float a = min(t.x, min(t.y, t.z));
+    return p + a * v;
+}
+
+vec4 get_color(vec3 p, vec3 v) {
+    vec3 r = intersect_ray(p, v);
+    float ax = abs(r.x);
+    float ay = abs(r.y);
+    float az = abs(r.z);
+    if (ax > ay && ax > az) {
+        if (r.x > 0) {
+            float tx = r.z / 2.0 + 0.5;
+            float ty = -r.y / 2.0 + 0.5;
+            return texture(tex1, vec2(tx, ty));
+        } else {
+            float tx = r.z / 2.0 + 0.5;
+            float ty = -r.y / 2.0 + 0.5;
+            return texture(tex1, vec2(tx, ty));
+        }
+    } else if (ay > ax && ay > az) {
+        if (r.y > 0) {
+            float tx = r.x / 2.0 + 0.5;
+            float ty = -r.z / 2.0 + 0.5;
+            return texture(tex2, vec2(tx, ty));
+        } else {
+            float tx = r.x / 2.0 + 0.5;
+            float ty = -r.z / 2.0 + 0.5;
+            return texture(tex3, vec2(tx, ty));
+        }
+    } else {
+        if (r.z > 0) {
+            float tx = r.x / 2.0 + 0.5;
+            float ty = -r.y / 2.0 + 0.5;
+            return texture(tex1, vec2(tx, ty));
+        } else {
+            float tx = r.x / 2.0 + 0.5;
+            float ty = -r.y / 2.0 + 0.5;
+            return texture(tex1, vec2(tx, ty));
+        }
+    }
+    return vec4(0, 1, 0, 1);
+}
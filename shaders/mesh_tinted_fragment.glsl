@@ -0,0 +1,11 @@
+#version 140
+
+in vec2 v_tex_coords;
+in vec3 v_tint;
+out vec4 color;
+
+uniform sampler2D tex;
+
+void main() {
+    color = texture(tex, v_tex_coords) * vec4(v_tint, 1.0);
+}
@@ -0,0 +1,20 @@
+#version 140
+
+in vec3 position;
+in vec2 tex_coords;
+in mat4 model;
+in vec3 tint;
+out vec2 v_tex_coords;
+out vec3 v_tint;
+
+uniform mat4 perspective;
+uniform mat4 view;
+uniform vec4 clip_plane;
+
+void main() {
+    v_tex_coords = tex_coords;
+    v_tint = tint;
+    vec4 world_position = model * vec4(position, 1.0);
+    gl_ClipDistance[0] = dot(world_position, clip_plane);
+    gl_Position = perspective * view * world_position;
+}
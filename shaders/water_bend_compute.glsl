@@ -0,0 +1,12 @@
+#version 430 core
+layout(local_size_x = 1, local_size_y = 1, local_size_z = 1) in;
+
+layout(r32f) writeonly uniform image2D tex1;
+uniform int x;
+uniform int y;
+uniform float strength;
+
+void main() {
+    ivec2 i = ivec2(x, y);
+    imageStore(tex1, i, vec4(-0.25 * strength, 0, 0, 0));
+}
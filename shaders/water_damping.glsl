@@ -0,0 +1,9 @@
+// Damps the waves near the pool walls. Expects `uniform int size`, the grid size.
+
+float get_d(vec2 i) {
+    float half_size = size / 2.0f;
+    float lx = max(i.x / half_size, 2.0f - i.x / half_size);
+    float ly = max(i.y / half_size, 2.0f - i.y / half_size);
+    float l = max(lx, ly);
+    return 0.95 * min(1.0, l / 0.2);
+}
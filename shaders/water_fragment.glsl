@@ -0,0 +1,52 @@
+#version 330 core
+
+in vec3 local_position;
+in vec3 world_position;
+in vec2 v_tex_coords;
+
+out vec4 color;
+
+#include "environment.glsl"
+
+uniform vec3 camera_position;
+uniform sampler2D normal_tex;
+uniform sampler2D reflection_tex;
+uniform bool reflection_enabled;
+uniform float reflection_distortion;
+
+float fresnel(vec3 v, vec3 n) {
+    float co = max(dot(v, n), 0);
+    float F0 = 0.14;
+    return F0 + (1 - F0) * pow(1 - co, 5);
+}
+
+void main() {
+    vec3 view_vector = normalize(camera_position - world_position);
+    vec3 normal = normalize(texture(normal_tex, v_tex_coords).xyz);
+    float n1n2 = 3.0/4.0;
+
+    bool below = dot(view_vector, normal) < 0;
+    if (below) {
+        normal.y = -normal.y;
+        n1n2 = 1.0 / n1n2;
+    }
+
+    vec3 reflected = reflect(-view_vector, normal);
+    vec3 refracted = refract(-view_vector, normal, n1n2);
+    float fresnel_value = fresnel(view_vector, normal);
+
+    vec4 reflected_color = get_color(local_position, reflected);
+    vec4 refracted_color = get_color(local_position, refracted);
+
+    if (reflection_enabled && !below) {
+        vec2 uv = gl_FragCoord.xy / vec2(textureSize(reflection_tex, 0));
+        vec4 duck = texture(reflection_tex, uv + normal.xz * reflection_distortion);
+        reflected_color = mix(reflected_color, vec4(duck.rgb, 1.0), duck.a);
+    }
+
+    if (!all(isnan(refracted))) {
+        color = mix(refracted_color, reflected_color, fresnel_value);
+    } else {
+        color = reflected_color;
+    }
+}
@@ -0,0 +1,26 @@
+#version 430 core
+layout(local_size_x = 8, local_size_y = 4, local_size_z = 1) in;
+
+layout(r32f) readonly uniform image2D tex1;
+layout(r32f) uniform image2D tex2;
+
+uniform float A;
+uniform float B;
+uniform int size;
+
+#include "water_damping.glsl"
+
+void main() {
+    ivec2 i = ivec2(gl_GlobalInvocationID.x, gl_GlobalInvocationID.y);
+    float z1 = imageLoad(tex1, ivec2(i.x, i.y + 1)).x;
+    float z2 = imageLoad(tex1, ivec2(i.x, i.y - 1)).x;
+    float z3 = imageLoad(tex1, ivec2(i.x + 1, i.y)).x;
+    float z4 = imageLoad(tex1, ivec2(i.x - 1, i.y)).x;
+    float z5 = imageLoad(tex1, ivec2(i.x, i.y)).x;
+    float z6 = imageLoad(tex2, ivec2(i.x, i.y)).x;
+
+    float d = get_d(i);
+
+    float c = d * (A * (z1 + z2 + z3 + z4) + B * z5 - z6);
+    imageStore(tex2, i, vec4(c, 0, 0, 0));
+}
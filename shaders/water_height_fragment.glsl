@@ -0,0 +1,33 @@
+#version 330 core
+
+uniform sampler2D current;
+uniform sampler2D previous;
+
+uniform float A;
+uniform float B;
+uniform int size;
+
+out float height;
+
+#include "water_damping.glsl"
+
+float load(sampler2D tex, ivec2 i) {
+    if (any(lessThan(i, ivec2(0))) || any(greaterThanEqual(i, ivec2(size)))) {
+        return 0.0;
+    }
+    return texelFetch(tex, i, 0).x;
+}
+
+void main() {
+    ivec2 i = ivec2(gl_FragCoord.xy);
+    float z1 = load(current, ivec2(i.x, i.y + 1));
+    float z2 = load(current, ivec2(i.x, i.y - 1));
+    float z3 = load(current, ivec2(i.x + 1, i.y));
+    float z4 = load(current, ivec2(i.x - 1, i.y));
+    float z5 = load(current, i);
+    float z6 = load(previous, i);
+
+    float d = get_d(i);
+
+    height = d * (A * (z1 + z2 + z3 + z4) + B * z5 - z6);
+}
@@ -0,0 +1,21 @@
+#version 430 core
+layout(local_size_x = 8, local_size_y = 4, local_size_z = 1) in;
+
+layout(r32f) readonly uniform image2D tex1;
+layout(rgba8) writeonly uniform image2D normal_tex;
+
+uniform int size;
+
+void main() {
+    ivec2 i = ivec2(gl_GlobalInvocationID.x, gl_GlobalInvocationID.y);
+    ivec2 ix1 = ivec2(min(i.x + 1, size - 1), i.y);
+    ivec2 ix2 = ivec2(max(i.x - 1, 0), i.y);
+    ivec2 iy1 = ivec2(i.x, min(i.y + 1, size - 1));
+    ivec2 iy2 = ivec2(i.x, max(i.y - 1, 0));
+    float y1 = imageLoad(tex1, ix1).x - imageLoad(tex1, ix2).x;
+    float y2 = imageLoad(tex1, iy1).x - imageLoad(tex1, iy2).x;
+    vec3 v1 = vec3(2.0 / size, y1, 0.0);
+    vec3 v2 = vec3(0.0, y2, 2.0 / size);
+    vec3 n = normalize(cross(v2.xyz, v1.xyz));
+    imageStore(normal_tex, i, vec4(n, 0.0));
+}
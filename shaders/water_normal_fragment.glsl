@@ -0,0 +1,20 @@
+#version 330 core
+
+uniform sampler2D current;
+uniform int size;
+
+out vec4 normal;
+
+void main() {
+    ivec2 i = ivec2(gl_FragCoord.xy);
+    ivec2 ix1 = ivec2(min(i.x + 1, size - 1), i.y);
+    ivec2 ix2 = ivec2(max(i.x - 1, 0), i.y);
+    ivec2 iy1 = ivec2(i.x, min(i.y + 1, size - 1));
+    ivec2 iy2 = ivec2(i.x, max(i.y - 1, 0));
+    float y1 = texelFetch(current, ix1, 0).x - texelFetch(current, ix2, 0).x;
+    float y2 = texelFetch(current, iy1, 0).x - texelFetch(current, iy2, 0).x;
+    vec3 v1 = vec3(2.0 / size, y1, 0.0);
+    vec3 v2 = vec3(0.0, y2, 2.0 / size);
+    vec3 n = normalize(cross(v2.xyz, v1.xyz));
+    normal = vec4(n, 0.0);
+}
@@ -0,0 +1,13 @@
+#version 430 core
+layout(local_size_x = 8, local_size_y = 4, local_size_z = 1) in;
+
+layout(r32f) uniform image2D tex1;
+layout(r32f) uniform image2D tex2;
+
+void main() {
+    ivec2 i = ivec2(gl_GlobalInvocationID.x, gl_GlobalInvocationID.y);
+    vec4 c1 = imageLoad(tex1, i);
+    vec4 c2 = imageLoad(tex2, i);
+    imageStore(tex1, i, c2);
+    imageStore(tex2, i, c1);
+}
@@ -0,0 +1,22 @@
+#version 330 core
+
+in vec3 position;
+in vec2 tex_coords;
+
+out vec3 local_position;
+out vec3 world_position;
+out vec2 v_tex_coords;
+
+uniform mat4 perspective;
+uniform mat4 view;
+uniform mat4 model;
+uniform float height;
+
+void main() {
+    v_tex_coords = tex_coords;
+    local_position = position;
+    local_position.y = height;
+    vec4 world_position4 = model * vec4(local_position, 1.0);
+    world_position = world_position4.xyz;
+    gl_Position = perspective * view * world_position4;
+}
@@ -0,0 +1,281 @@
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::time::SystemTime;
+
+use glium::backend::Facade;
+use glium::program::ComputeShader;
+use glium::texture::{MipmapsOption, RawImage2d, Texture2d};
+use glium::{Program, ProgramCreationError};
+
+use crate::assets::asset_paths::AssetPaths;
+use crate::assets::embedded_assets::get_embedded_asset;
+use crate::assets::shader_error::{ShaderError, ShaderStage};
+use crate::assets::shader_preprocessor::{preprocess_shader, PreprocessedShader};
+use crate::meshes::mesh::Mesh;
+use crate::meshes::parse_mesh::parse_mesh;
+
+const CHECKERBOARD_SIZE: u32 = 64;
+const CHECKERBOARD_CELL: u32 = 8;
+
+/// Loads textures, meshes and shaders by key, a path relative to the asset paths. A
+/// file found on disk is preferred over the copy embedded in the executable, except for
+/// shaders, which come from the executable unless `shaders_from_disk` is set. Only the
+/// shaders and the default duck's mesh and texture are embedded. Textures and meshes
+/// are loaded once and then shared from the cache. The files read from disk are
+/// remembered, so that changes to them can be detected.
+pub struct AssetManager {
+    paths: AssetPaths,
+    shaders_from_disk: bool,
+    textures: HashMap<PathBuf, Rc<Texture2d>>,
+    meshes: HashMap<PathBuf, Rc<Mesh>>,
+    shader_files: HashMap<PathBuf, Vec<PathBuf>>,
+    files: HashMap<PathBuf, (PathBuf, Option<SystemTime>)>,
+}
+
+impl AssetManager {
+    pub fn new(paths: AssetPaths, shaders_from_disk: bool) -> Self {
+        Self {
+            paths,
+            shaders_from_disk,
+            textures: HashMap::new(),
+            meshes: HashMap::new(),
+            shader_files: HashMap::new(),
+            files: HashMap::new(),
+        }
+    }
+
+    /// Returns the keys of the files modified since they were last read and drops them
+    /// from the cache, so that the next request loads them again.
+    pub fn poll_changes(&mut self) -> Vec<PathBuf> {
+        let mut changed = vec![];
+        for (key, (path, modified)) in self.files.iter_mut() {
+            let current = get_modified(path);
+            if current != *modified {
+                *modified = current;
+                changed.push(key.clone());
+            }
+        }
+
+        for key in &changed {
+            self.textures.remove(key);
+            self.meshes.remove(key);
+        }
+        changed
+    }
+
+    /// Returns the texture with mipmaps. A texture that cannot be loaded is reported and
+    /// replaced with a checkerboard, so a missing asset does not stop the program.
+    pub fn get_texture(&mut self, display: &impl Facade, key: &Path) -> Rc<Texture2d> {
+        if let Some(texture) = self.textures.get(key) {
+            return texture.clone();
+        }
+
+        let texture = self.load_texture(display, key).unwrap_or_else(|error| {
+            eprintln!("{}; using a checkerboard instead", error);
+            get_checkerboard_texture(display)
+        });
+        let texture = Rc::new(texture);
+        self.textures.insert(key.to_path_buf(), texture.clone());
+        texture
+    }
+
+    pub fn get_mesh(&mut self, display: &impl Facade, key: &Path) -> Result<Rc<Mesh>, String> {
+        if let Some(mesh) = self.meshes.get(key) {
+            return Ok(mesh.clone());
+        }
+
+        let mesh = Rc::new(self.load_mesh(display, key)?);
+        self.meshes.insert(key.to_path_buf(), mesh.clone());
+        Ok(mesh)
+    }
+
+    /// Compiles a program from vertex and fragment shader files. The error names the
+    /// failing stage and file and carries the driver's log.
+    pub fn load_program(
+        &mut self,
+        display: &impl Facade,
+        vertex: &Path,
+        fragment: &Path,
+    ) -> Result<Program, ShaderError> {
+        let vertex_shader = self.read_shader(vertex, ShaderStage::Vertex)?;
+        let fragment_shader = self.read_shader(fragment, ShaderStage::Fragment)?;
+        Program::from_source(
+            display,
+            &vertex_shader.source,
+            &fragment_shader.source,
+            None,
+        )
+        .map_err(|error| match error {
+            ProgramCreationError::CompilationError(log, shader_type) => {
+                let stage = ShaderStage::from_shader_type(shader_type);
+                let shader = match stage {
+                    ShaderStage::Fragment => &fragment_shader,
+                    _ => &vertex_shader,
+                };
+                ShaderError::from_compile_log(stage, shader, &log)
+            }
+            ProgramCreationError::LinkingError(log) => ShaderError::new(
+                ShaderStage::Link,
+                format!("{} and {}", vertex.display(), fragment.display()),
+                log,
+            ),
+            error => ShaderError::new(
+                ShaderStage::Link,
+                format!("{} and {}", vertex.display(), fragment.display()),
+                error.to_string(),
+            ),
+        })
+    }
+
+    pub fn load_compute_shader(
+        &mut self,
+        display: &impl Facade,
+        key: &Path,
+    ) -> Result<ComputeShader, ShaderError> {
+        let shader = self.read_shader(key, ShaderStage::Compute)?;
+        ComputeShader::from_source(display, &shader.source).map_err(|error| match error {
+            ProgramCreationError::CompilationError(log, _) => {
+                ShaderError::from_compile_log(ShaderStage::Compute, &shader, &log)
+            }
+            error => ShaderError::new(
+                ShaderStage::Compute,
+                key.display().to_string(),
+                error.to_string(),
+            ),
+        })
+    }
+
+    /// Rebuilds the program when one of its files, or a file they include, is among the
+    /// changed keys. When the new sources fail to build, the error is printed and the
+    /// previous program is kept.
+    pub fn reload_program(
+        &mut self,
+        display: &impl Facade,
+        program: &mut Program,
+        vertex: &Path,
+        fragment: &Path,
+        changed: &[PathBuf],
+    ) {
+        if !self.is_shader_changed(vertex, changed) && !self.is_shader_changed(fragment, changed) {
+            return;
+        }
+
+        match self.load_program(display, vertex, fragment) {
+            Ok(reloaded) => {
+                *program = reloaded;
+                println!("Reloaded {} and {}", vertex.display(), fragment.display());
+            }
+            Err(error) => eprintln!("{}; keeping the previous program", error),
+        }
+    }
+
+    pub fn reload_compute_shader(
+        &mut self,
+        display: &impl Facade,
+        shader: &mut ComputeShader,
+        key: &Path,
+        changed: &[PathBuf],
+    ) {
+        if !self.is_shader_changed(key, changed) {
+            return;
+        }
+
+        match self.load_compute_shader(display, key) {
+            Ok(reloaded) => {
+                *shader = reloaded;
+                println!("Reloaded {}", key.display());
+            }
+            Err(error) => eprintln!("{}; keeping the previous shader", error),
+        }
+    }
+
+    fn is_shader_changed(&self, key: &Path, changed: &[PathBuf]) -> bool {
+        let files = self.shader_files.get(key);
+        changed.iter().any(|changed_key| {
+            changed_key == key || files.is_some_and(|files| files.contains(changed_key))
+        })
+    }
+
+    fn read_shader(
+        &mut self,
+        key: &Path,
+        stage: ShaderStage,
+    ) -> Result<PreprocessedShader, ShaderError> {
+        let shader = preprocess_shader(key, &mut |file| self.read_shader_file(file))
+            .map_err(|error| ShaderError::new(stage, key.display().to_string(), error))?;
+        self.shader_files
+            .insert(key.to_path_buf(), shader.files.clone());
+        Ok(shader)
+    }
+
+    fn read_shader_file(&mut self, key: &Path) -> Result<String, String> {
+        if !self.shaders_from_disk {
+            if let Some(bytes) = get_embedded_asset(key) {
+                return String::from_utf8(bytes.to_vec())
+                    .map_err(|error| format!("cannot read {}: {}", key.display(), error));
+            }
+        }
+        self.read_text(key)
+    }
+
+    fn load_texture(&mut self, display: &impl Facade, key: &Path) -> Result<Texture2d, String> {
+        let bytes = self.read(key)?;
+        let image = image::load_from_memory(&bytes)
+            .map_err(|error| format!("cannot decode {}: {}", key.display(), error))?
+            .to_rgba8();
+        let image_dimensions = image.dimensions();
+        let image = RawImage2d::from_raw_rgba(image.into_raw(), image_dimensions);
+        Texture2d::with_mipmaps(display, image, MipmapsOption::AutoGeneratedMipmaps)
+            .map_err(|error| format!("cannot create texture {}: {}", key.display(), error))
+    }
+
+    fn load_mesh(&mut self, display: &impl Facade, key: &Path) -> Result<Mesh, String> {
+        parse_mesh(&self.read_text(key)?, display)
+            .map_err(|error| format!("cannot parse {}: {}", key.display(), error))
+    }
+
+    fn read_text(&mut self, key: &Path) -> Result<String, String> {
+        let bytes = self.read(key)?;
+        String::from_utf8(bytes.into_owned())
+            .map_err(|error| format!("cannot read {}: {}", key.display(), error))
+    }
+
+    /// Reads the asset from the first file found in the asset paths, or from the
+    /// embedded copy when there is no such file.
+    fn read(&mut self, key: &Path) -> Result<Cow<'static, [u8]>, String> {
+        match self.paths.resolve(key) {
+            Ok(path) => {
+                let bytes = fs::read(&path)
+                    .map_err(|error| format!("cannot read {}: {}", path.display(), error))?;
+                let modified = get_modified(&path);
+                self.files.insert(key.to_path_buf(), (path, modified));
+                Ok(Cow::Owned(bytes))
+            }
+            Err(error) => get_embedded_asset(key).map(Cow::Borrowed).ok_or(error),
+        }
+    }
+}
+
+fn get_modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
+
+fn get_checkerboard_texture(display: &impl Facade) -> Texture2d {
+    let pixels = (0..CHECKERBOARD_SIZE)
+        .flat_map(|y| (0..CHECKERBOARD_SIZE).map(move |x| (x, y)))
+        .flat_map(|(x, y)| {
+            if (x / CHECKERBOARD_CELL + y / CHECKERBOARD_CELL).is_multiple_of(2) {
+                [255, 0, 255, 255]
+            } else {
+                [0, 0, 0, 255]
+            }
+        })
+        .collect();
+    let image = RawImage2d::from_raw_rgba(pixels, (CHECKERBOARD_SIZE, CHECKERBOARD_SIZE));
+    Texture2d::new(display, image).unwrap()
+}
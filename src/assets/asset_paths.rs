@@ -0,0 +1,58 @@
+use std::env;
+use std::path::{Path, PathBuf};
+
+/// How many directories above the executable are searched, so that assets in the
+/// repository root are found from `target/debug` and `target/release`.
+const EXECUTABLE_ANCESTORS: usize = 3;
+
+/// The directories relative asset paths are looked up in, in order: the configured
+/// asset root, the executable's directory and its parents, and the working directory.
+#[derive(Debug, Clone)]
+pub struct AssetPaths {
+    roots: Vec<PathBuf>,
+}
+
+impl AssetPaths {
+    pub fn new(asset_root: Option<&Path>) -> Self {
+        let mut roots: Vec<PathBuf> = asset_root.into_iter().map(Path::to_path_buf).collect();
+        if let Ok(executable) = env::current_exe() {
+            roots.extend(
+                executable
+                    .ancestors()
+                    .skip(1)
+                    .take(EXECUTABLE_ANCESTORS + 1)
+                    .map(Path::to_path_buf),
+            );
+        }
+        if let Ok(current_dir) = env::current_dir() {
+            roots.push(current_dir);
+        }
+        Self { roots }
+    }
+
+    /// Returns the first existing file for `path`, or an error listing every location
+    /// that was tried.
+    pub fn resolve(&self, path: &Path) -> Result<PathBuf, String> {
+        let candidates: Vec<PathBuf> = if path.is_absolute() {
+            vec![path.to_path_buf()]
+        } else {
+            self.roots.iter().map(|root| root.join(path)).collect()
+        };
+
+        candidates
+            .iter()
+            .find(|candidate| candidate.is_file())
+            .cloned()
+            .ok_or_else(|| {
+                let tried: Vec<String> = candidates
+                    .iter()
+                    .map(|candidate| candidate.display().to_string())
+                    .collect();
+                format!(
+                    "cannot find {}, tried: {}",
+                    path.display(),
+                    tried.join(", ")
+                )
+            })
+    }
+}
@@ -0,0 +1,16 @@
+use std::path::Path;
+
+/// The shaders and the default mesh with their contents, generated by `build.rs` from the
+/// same list of files it checks.
+const EMBEDDED_ASSETS: &[(&str, &[u8])] = include!(concat!(env!("OUT_DIR"), "/embedded_assets.rs"));
+
+/// Returns the copy of a shader or the default mesh built into the executable, so that
+/// the default scene runs even when no asset files can be found. Textures are not
+/// embedded and fall back to a checkerboard instead.
+pub fn get_embedded_asset(key: &Path) -> Option<&'static [u8]> {
+    let key = key.to_str()?;
+    EMBEDDED_ASSETS
+        .iter()
+        .find(|(embedded_key, _)| *embedded_key == key)
+        .map(|(_, content)| *content)
+}
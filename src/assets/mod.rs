@@ -0,0 +1,5 @@
+pub mod asset_manager;
+pub mod asset_paths;
+pub mod embedded_assets;
+pub mod shader_error;
+pub mod shader_preprocessor;
@@ -0,0 +1,165 @@
+use std::error::Error;
+use std::fmt;
+
+use glium::program::ShaderType;
+
+use crate::assets::shader_preprocessor::PreprocessedShader;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShaderStage {
+    Vertex,
+    Fragment,
+    Compute,
+    Link,
+}
+
+impl ShaderStage {
+    pub fn from_shader_type(shader_type: ShaderType) -> Self {
+        match shader_type {
+            ShaderType::Fragment => Self::Fragment,
+            ShaderType::Compute => Self::Compute,
+            _ => Self::Vertex,
+        }
+    }
+}
+
+impl fmt::Display for ShaderStage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Vertex => "vertex shader",
+            Self::Fragment => "fragment shader",
+            Self::Compute => "compute shader",
+            Self::Link => "program",
+        };
+        f.write_str(name)
+    }
+}
+
+/// A shader that could not be read, compiled or linked. The log is the driver's info
+/// log with its line numbers pointing into the shader files rather than the
+/// preprocessed source.
+#[derive(Debug, Clone)]
+pub struct ShaderError {
+    pub stage: ShaderStage,
+    pub source_name: String,
+    pub log: String,
+}
+
+impl ShaderError {
+    pub fn new(stage: ShaderStage, source_name: String, log: String) -> Self {
+        Self {
+            stage,
+            source_name,
+            log,
+        }
+    }
+
+    pub fn from_compile_log(stage: ShaderStage, shader: &PreprocessedShader, log: &str) -> Self {
+        let log = log
+            .lines()
+            .map(|line| map_log_line(line, shader))
+            .collect::<Vec<_>>()
+            .join("\n");
+        Self::new(stage, shader.files[0].display().to_string(), log)
+    }
+}
+
+impl fmt::Display for ShaderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "cannot build {} {}:\n{}",
+            self.stage,
+            self.source_name,
+            self.log.trim_end()
+        )
+    }
+}
+
+impl Error for ShaderError {}
+
+/// Replaces the `0:line` or `0(line)` location drivers put in their logs with the
+/// file and line it came from. Lines without a recognised location are kept as they are.
+fn map_log_line(line: &str, shader: &PreprocessedShader) -> String {
+    let word_starts = std::iter::once(0).chain(line.match_indices(' ').map(|(index, _)| index + 1));
+    for start in word_starts {
+        let rest = &line[start..];
+        let (after, parenthesized) = match rest.strip_prefix("0:") {
+            Some(after) => (after, false),
+            None => match rest.strip_prefix("0(") {
+                Some(after) => (after, true),
+                None => continue,
+            },
+        };
+
+        let digits = after.len() - after.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+        let Ok(number) = after[..digits].parse() else {
+            continue;
+        };
+        let Some((file, file_line)) = shader.get_source_location(number) else {
+            continue;
+        };
+
+        let mut remainder = &after[digits..];
+        if parenthesized {
+            remainder = remainder.strip_prefix(')').unwrap_or(remainder);
+        }
+        return format!(
+            "{}{}:{}{}",
+            &line[..start],
+            file.display(),
+            file_line,
+            remainder
+        );
+    }
+    line.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+    use crate::assets::shader_preprocessor::preprocess_shader;
+
+    fn get_shader() -> PreprocessedShader {
+        preprocess_shader(Path::new("shaders/water.glsl"), &mut |key| {
+            Ok(match key.to_str().unwrap() {
+                "shaders/water.glsl" => {
+                    "#version 330 core\n#include \"light.glsl\"\nvoid main() {}\n"
+                }
+                _ => "// light\nvec3 light = vec3(1.0);\n",
+            }
+            .to_string())
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn mesa_locations_are_mapped() {
+        let line = map_log_line("0:4(5): error: syntax error", &get_shader());
+        assert_eq!(line, "shaders/water.glsl:3(5): error: syntax error");
+    }
+
+    #[test]
+    fn nvidia_locations_are_mapped() {
+        let line = map_log_line("0(4) : error C0000: syntax error", &get_shader());
+        assert_eq!(line, "shaders/water.glsl:3 : error C0000: syntax error");
+    }
+
+    #[test]
+    fn included_lines_are_mapped_to_their_file() {
+        let line = map_log_line("ERROR: 0:3: 'vec3' : syntax error", &get_shader());
+        assert_eq!(line, "ERROR: shaders/light.glsl:2: 'vec3' : syntax error");
+    }
+
+    #[test]
+    fn unknown_lines_are_kept() {
+        let shader = get_shader();
+        assert_eq!(
+            map_log_line("error: linking failed", &shader),
+            "error: linking failed"
+        );
+        assert_eq!(map_log_line("0:99(1): error", &shader), "0:99(1): error");
+    }
+}
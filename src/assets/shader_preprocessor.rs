@@ -0,0 +1,165 @@
+use std::path::{Path, PathBuf};
+
+const INCLUDE_DIRECTIVE: &str = "#include";
+
+/// GLSL source with its `#include "file"` lines replaced by the included files. Paths are
+/// relative to the including file, and every file is included at most once.
+pub struct PreprocessedShader {
+    pub source: String,
+    /// The keys of the shader and of every file it includes.
+    pub files: Vec<PathBuf>,
+    /// For every line of `source`, the index of its file and its line number there.
+    lines: Vec<(usize, usize)>,
+}
+
+impl PreprocessedShader {
+    /// Maps a 1-based line number of the preprocessed source back to its file and line.
+    pub fn get_source_location(&self, line: usize) -> Option<(&Path, usize)> {
+        let (file, file_line) = *self.lines.get(line.checked_sub(1)?)?;
+        Some((&self.files[file], file_line))
+    }
+}
+
+pub fn preprocess_shader(
+    key: &Path,
+    read: &mut impl FnMut(&Path) -> Result<String, String>,
+) -> Result<PreprocessedShader, String> {
+    let mut shader = PreprocessedShader {
+        source: String::new(),
+        files: vec![],
+        lines: vec![],
+    };
+    append_file(key, read, &mut shader, &mut vec![])?;
+    Ok(shader)
+}
+
+fn append_file(
+    key: &Path,
+    read: &mut impl FnMut(&Path) -> Result<String, String>,
+    shader: &mut PreprocessedShader,
+    stack: &mut Vec<PathBuf>,
+) -> Result<(), String> {
+    if stack.iter().any(|including| including == key) {
+        return Err(format!("{} includes itself", key.display()));
+    }
+    if shader.files.iter().any(|included| included == key) {
+        return Ok(());
+    }
+
+    let content = read(key)?;
+    let file = shader.files.len();
+    shader.files.push(key.to_path_buf());
+    stack.push(key.to_path_buf());
+
+    for (index, line) in content.lines().enumerate() {
+        match line.trim().strip_prefix(INCLUDE_DIRECTIVE) {
+            Some(argument) => {
+                let name = argument
+                    .trim()
+                    .strip_prefix('"')
+                    .and_then(|argument| argument.strip_suffix('"'))
+                    .ok_or_else(|| {
+                        format!(
+                            "{}:{}: expected #include \"file\"",
+                            key.display(),
+                            index + 1
+                        )
+                    })?;
+                let included = get_included_key(key, name);
+                append_file(&included, read, shader, stack)?;
+            }
+            None => {
+                shader.source.push_str(line);
+                shader.source.push('\n');
+                shader.lines.push((file, index + 1));
+            }
+        }
+    }
+
+    stack.pop();
+    Ok(())
+}
+
+fn get_included_key(key: &Path, name: &str) -> PathBuf {
+    key.parent().unwrap_or(Path::new("")).join(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    fn preprocess(files: &[(&str, &str)]) -> Result<PreprocessedShader, String> {
+        let files: HashMap<PathBuf, String> = files
+            .iter()
+            .map(|(key, content)| (PathBuf::from(key), content.to_string()))
+            .collect();
+        preprocess_shader(Path::new("shaders/main.glsl"), &mut |key| {
+            files
+                .get(key)
+                .cloned()
+                .ok_or_else(|| format!("{}: not found", key.display()))
+        })
+    }
+
+    #[test]
+    fn include_cycles_are_rejected() {
+        let error = preprocess(&[
+            ("shaders/main.glsl", "#include \"a.glsl\"\n"),
+            ("shaders/a.glsl", "#include \"b.glsl\"\n"),
+            ("shaders/b.glsl", "#include \"a.glsl\"\n"),
+        ])
+        .err()
+        .unwrap();
+        assert_eq!(error, "shaders/a.glsl includes itself");
+    }
+
+    #[test]
+    fn files_are_included_once() {
+        let shader = preprocess(&[
+            (
+                "shaders/main.glsl",
+                "#include \"common.glsl\"\n#include \"common.glsl\"\nvoid main() {}\n",
+            ),
+            ("shaders/common.glsl", "float half_value = 0.5;\n"),
+        ])
+        .unwrap();
+        assert_eq!(shader.source, "float half_value = 0.5;\nvoid main() {}\n");
+        assert_eq!(shader.files.len(), 2);
+    }
+
+    #[test]
+    fn lines_map_back_to_their_files() {
+        let shader = preprocess(&[
+            (
+                "shaders/main.glsl",
+                "#version 330 core\n#include \"lib/common.glsl\"\nvoid main() {}\n",
+            ),
+            (
+                "shaders/lib/common.glsl",
+                "// common\nfloat f() { return 1.0; }\n",
+            ),
+        ])
+        .unwrap();
+        let location = |line| {
+            shader
+                .get_source_location(line)
+                .map(|(file, file_line)| (file.to_str().unwrap(), file_line))
+        };
+        assert_eq!(location(1), Some(("shaders/main.glsl", 1)));
+        assert_eq!(location(2), Some(("shaders/lib/common.glsl", 1)));
+        assert_eq!(location(3), Some(("shaders/lib/common.glsl", 2)));
+        assert_eq!(location(4), Some(("shaders/main.glsl", 3)));
+        assert_eq!(location(0), None);
+        assert_eq!(location(5), None);
+    }
+
+    #[test]
+    fn malformed_includes_are_reported() {
+        let error = preprocess(&[("shaders/main.glsl", "\n#include common.glsl\n")])
+            .err()
+            .unwrap();
+        assert_eq!(error, "shaders/main.glsl:2: expected #include \"file\"");
+    }
+}
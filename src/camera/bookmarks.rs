@@ -0,0 +1,74 @@
+use std::fs;
+use std::io;
+
+use nalgebra::{Point3, Quaternion, UnitQuaternion, Vector3};
+
+use crate::camera::camera::Camera;
+
+#[derive(Debug, Clone)]
+pub struct Bookmark {
+    pub name: String,
+    pub position: Point3<f32>,
+    pub orientation: UnitQuaternion<f32>,
+}
+
+impl Bookmark {
+    pub fn from_camera(name: String, camera: &dyn Camera) -> Self {
+        Self {
+            name,
+            position: camera.get_position(),
+            orientation: UnitQuaternion::face_towards(&camera.get_direction(), &camera.get_up()),
+        }
+    }
+
+    pub fn get_direction(&self) -> Vector3<f32> {
+        self.orientation * Vector3::new(0.0, 0.0, 1.0)
+    }
+}
+
+/// Writes the bookmarks as a count line followed by one `x y z qi qj qk qw name` line
+/// per bookmark.
+pub fn write_bookmarks(file_name: &str, bookmarks: &[Bookmark]) -> io::Result<()> {
+    let mut content = format!("{}\n", bookmarks.len());
+    for bookmark in bookmarks {
+        let q = bookmark.orientation.quaternion();
+        content += &format!(
+            "{} {} {} {} {} {} {} {}\n",
+            bookmark.position.x,
+            bookmark.position.y,
+            bookmark.position.z,
+            q.i,
+            q.j,
+            q.k,
+            q.w,
+            bookmark.name
+        );
+    }
+    fs::write(file_name, content)
+}
+
+pub fn read_bookmarks(file_name: &str) -> io::Result<Vec<Bookmark>> {
+    let content = fs::read_to_string(file_name)?;
+    let mut lines = content.lines();
+    let count = parse_value::<usize>(lines.next())?;
+    let mut bookmarks = Vec::with_capacity(count);
+    for _ in 0..count {
+        let mut values = lines.next().unwrap_or_default().splitn(8, ' ');
+        let mut next = || parse_value::<f32>(values.next());
+        let position = Point3::new(next()?, next()?, next()?);
+        let (i, j, k, w) = (next()?, next()?, next()?, next()?);
+        let name = values.next().unwrap_or_default().to_string();
+        bookmarks.push(Bookmark {
+            name,
+            position,
+            orientation: UnitQuaternion::from_quaternion(Quaternion::new(w, i, j, k)),
+        });
+    }
+    Ok(bookmarks)
+}
+
+fn parse_value<T: std::str::FromStr>(value: Option<&str>) -> io::Result<T> {
+    value
+        .and_then(|value| value.trim().parse::<T>().ok())
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "malformed bookmark file"))
+}
@@ -0,0 +1,24 @@
+use nalgebra::{Matrix4, Point3, Vector2, Vector3};
+use winit::event::VirtualKeyCode;
+
+pub trait Camera {
+    fn get_view(&self) -> Matrix4<f32>;
+
+    fn get_position(&self) -> Point3<f32>;
+
+    fn get_direction(&self) -> Vector3<f32>;
+
+    fn get_up(&self) -> Vector3<f32> {
+        Vector3::new(0.0, 1.0, 0.0)
+    }
+
+    /// Called with the cursor movement in pixels while the middle mouse button is held.
+    fn rotate(&mut self, _delta: Vector2<f32>) {}
+
+    /// Called with the scroll wheel delta in lines.
+    fn zoom(&mut self, _delta: f32) {}
+
+    fn handle_key(&mut self, _key: VirtualKeyCode, _pressed: bool) {}
+
+    fn update(&mut self, _dt: f32) {}
+}
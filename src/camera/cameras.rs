@@ -0,0 +1,141 @@
+use nalgebra::{Matrix4, Point3};
+
+use crate::camera::bookmarks::Bookmark;
+use crate::camera::camera::Camera;
+use crate::camera::chase_camera::ChaseCamera;
+use crate::camera::fly_through_camera::FlyThroughCamera;
+use crate::camera::free_fly_camera::FreeFlyCamera;
+use crate::camera::orbit_camera::OrbitCamera;
+use crate::camera::pose_camera::PoseCamera;
+use crate::camera::projection::Projection;
+use crate::scene::scene::CameraDescription;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum CameraMode {
+    Orbit,
+    FreeFly,
+    Chase,
+    FlyThrough,
+    /// A recorded pose shown as it is, while replaying inputs.
+    Pose,
+}
+
+pub struct Cameras {
+    mode: CameraMode,
+    pub orbit: OrbitCamera,
+    pub free_fly: FreeFlyCamera,
+    pub chase: ChaseCamera,
+    pub fly_through: FlyThroughCamera,
+    pub pose: PoseCamera,
+    pub projection: Projection,
+    pub bookmarks: Vec<Bookmark>,
+}
+
+impl Cameras {
+    pub fn new(width: u32, height: u32, description: &CameraDescription) -> Self {
+        let mut orbit = OrbitCamera::new(description.distance);
+        orbit.target = Point3::from(description.target);
+        orbit.set_angles(description.yaw.to_radians(), description.pitch.to_radians());
+        let free_fly = FreeFlyCamera::new(orbit.get_position(), orbit.get_direction());
+        let chase = ChaseCamera::new(orbit.get_position());
+
+        Self {
+            mode: CameraMode::Orbit,
+            orbit,
+            free_fly,
+            chase,
+            fly_through: FlyThroughCamera::new(),
+            pose: PoseCamera::new(),
+            projection: Projection::new(width, height),
+            bookmarks: vec![],
+        }
+    }
+
+    pub fn get_mode(&self) -> CameraMode {
+        self.mode
+    }
+
+    /// Switches the active camera. The free-fly and chase cameras start from the pose
+    /// of the previously active camera so that switching does not jump.
+    pub fn set_mode(&mut self, mode: CameraMode) {
+        if mode == self.mode {
+            return;
+        }
+
+        let active = self.get_active();
+        let (position, direction) = (active.get_position(), active.get_direction());
+        match mode {
+            CameraMode::FreeFly => self.free_fly.look_from(position, direction),
+            CameraMode::Chase => self.chase.start_from(position, direction),
+            CameraMode::Orbit | CameraMode::FlyThrough | CameraMode::Pose => {}
+        }
+
+        self.mode = mode;
+    }
+
+    pub fn get_active(&self) -> &dyn Camera {
+        match self.mode {
+            CameraMode::Orbit => &self.orbit,
+            CameraMode::FreeFly => &self.free_fly,
+            CameraMode::Chase => &self.chase,
+            CameraMode::FlyThrough => &self.fly_through,
+            CameraMode::Pose => &self.pose,
+        }
+    }
+
+    pub fn get_active_mut(&mut self) -> &mut dyn Camera {
+        match self.mode {
+            CameraMode::Orbit => &mut self.orbit,
+            CameraMode::FreeFly => &mut self.free_fly,
+            CameraMode::Chase => &mut self.chase,
+            CameraMode::FlyThrough => &mut self.fly_through,
+            CameraMode::Pose => &mut self.pose,
+        }
+    }
+
+    pub fn add_bookmark(&mut self, name: String) {
+        let bookmark = Bookmark::from_camera(name, self.get_active());
+        self.bookmarks.push(bookmark);
+    }
+
+    /// Moves the free-fly camera to the bookmark and makes it active.
+    pub fn go_to_bookmark(&mut self, index: usize) {
+        if let Some(bookmark) = self.bookmarks.get(index).cloned() {
+            self.look_from(&bookmark);
+        }
+    }
+
+    /// Moves the free-fly camera to the pose and makes it active. The free-fly camera
+    /// has no roll and limits its pitch, so the view can differ slightly from the pose.
+    pub fn look_from(&mut self, pose: &Bookmark) {
+        self.free_fly.look_from(pose.position, pose.get_direction());
+        self.mode = CameraMode::FreeFly;
+    }
+
+    /// Shows the scene from exactly the pose, roll included.
+    pub fn show_pose(&mut self, pose: &Bookmark) {
+        self.pose.set_pose(pose);
+        self.mode = CameraMode::Pose;
+    }
+
+    pub fn play_bookmarks(&mut self) {
+        self.fly_through.play(self.bookmarks.clone());
+        self.mode = CameraMode::FlyThrough;
+    }
+
+    pub fn resize(&mut self, width: u32, height: u32) {
+        self.projection.resize(width, height);
+    }
+
+    pub fn get_perspective(&self) -> Matrix4<f32> {
+        self.projection.get_matrix()
+    }
+
+    pub fn get_view(&self) -> Matrix4<f32> {
+        self.get_active().get_view()
+    }
+
+    pub fn get_position(&self) -> Point3<f32> {
+        self.get_active().get_position()
+    }
+}
@@ -0,0 +1,76 @@
+use nalgebra::{Matrix4, Point3, Vector2, Vector3};
+
+use crate::camera::camera::Camera;
+
+pub struct ChaseCamera {
+    position: Point3<f32>,
+    look_at: Point3<f32>,
+    target: Point3<f32>,
+    heading: Vector2<f32>,
+    pub distance: f32,
+    pub height: f32,
+    pub smoothing: f32,
+}
+
+impl ChaseCamera {
+    pub fn new(position: Point3<f32>) -> Self {
+        Self {
+            position,
+            look_at: Point3::origin(),
+            target: Point3::origin(),
+            heading: Vector2::new(1.0, 0.0),
+            distance: 1.5,
+            height: 0.75,
+            smoothing: 4.0,
+        }
+    }
+
+    /// Sets the followed point and its heading on the water plane, usually the spline
+    /// tangent of the followed duck.
+    pub fn set_target(&mut self, target: Point3<f32>, heading: Vector2<f32>) {
+        self.target = target;
+        if heading.norm() > f32::EPSILON {
+            self.heading = heading.normalize();
+        }
+    }
+
+    /// Places the camera at the given pose, from where `update` eases it behind the
+    /// target.
+    pub fn start_from(&mut self, position: Point3<f32>, direction: Vector3<f32>) {
+        self.position = position;
+        self.look_at = position + direction;
+    }
+
+    fn get_desired_position(&self) -> Point3<f32> {
+        self.target
+            + Vector3::new(
+                -self.heading.x * self.distance,
+                self.height,
+                -self.heading.y * self.distance,
+            )
+    }
+}
+
+impl Camera for ChaseCamera {
+    fn get_view(&self) -> Matrix4<f32> {
+        Matrix4::look_at_rh(&self.position, &self.look_at, &Vector3::new(0.0, 1.0, 0.0))
+    }
+
+    fn get_position(&self) -> Point3<f32> {
+        self.position
+    }
+
+    fn get_direction(&self) -> Vector3<f32> {
+        (self.look_at - self.position).normalize()
+    }
+
+    fn zoom(&mut self, delta: f32) {
+        self.distance = (self.distance - delta * 0.1).max(0.2);
+    }
+
+    fn update(&mut self, dt: f32) {
+        let alpha = 1.0 - (-self.smoothing * dt).exp();
+        self.position += (self.get_desired_position() - self.position) * alpha;
+        self.look_at += (self.target - self.look_at) * alpha;
+    }
+}
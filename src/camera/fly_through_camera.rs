@@ -0,0 +1,130 @@
+use nalgebra::{Matrix4, Point3, UnitQuaternion, Vector3};
+
+use crate::camera::bookmarks::Bookmark;
+use crate::camera::camera::Camera;
+
+pub struct FlyThroughCamera {
+    keyframes: Vec<Bookmark>,
+    time: f32,
+    pub duration: f32,
+    pub looping: bool,
+    pub playing: bool,
+}
+
+impl Default for FlyThroughCamera {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FlyThroughCamera {
+    pub fn new() -> Self {
+        Self {
+            keyframes: vec![],
+            time: 0.0,
+            duration: 10.0,
+            looping: false,
+            playing: false,
+        }
+    }
+
+    pub fn play(&mut self, keyframes: Vec<Bookmark>) {
+        self.keyframes = keyframes;
+        self.time = 0.0;
+        self.playing = !self.keyframes.is_empty();
+    }
+
+    pub fn get_progress(&self) -> f32 {
+        if self.duration > 0.0 {
+            (self.time / self.duration).min(1.0)
+        } else {
+            1.0
+        }
+    }
+
+    /// Returns the segment index and the local parameter within it for the current time.
+    fn get_segment(&self) -> (usize, f32) {
+        let segments = self.keyframes.len().saturating_sub(1);
+        if segments == 0 {
+            return (0, 0.0);
+        }
+
+        let s = self.get_progress() * segments as f32;
+        let segment = (s.floor() as usize).min(segments - 1);
+        (segment, s - segment as f32)
+    }
+
+    fn get_keyframe(&self, index: isize) -> &Bookmark {
+        let last = self.keyframes.len() as isize - 1;
+        &self.keyframes[index.clamp(0, last) as usize]
+    }
+
+    fn get_orientation(&self) -> UnitQuaternion<f32> {
+        if self.keyframes.is_empty() {
+            return UnitQuaternion::identity();
+        }
+
+        let (segment, t) = self.get_segment();
+        let from = self.get_keyframe(segment as isize).orientation;
+        let to = self.get_keyframe(segment as isize + 1).orientation;
+        from.try_slerp(&to, t, 1.0e-6).unwrap_or(from)
+    }
+}
+
+impl Camera for FlyThroughCamera {
+    fn get_view(&self) -> Matrix4<f32> {
+        let position = self.get_position();
+        Matrix4::look_at_rh(
+            &position,
+            &(position + self.get_direction()),
+            &self.get_up(),
+        )
+    }
+
+    /// Evaluates a Catmull-Rom spline through the bookmark positions.
+    fn get_position(&self) -> Point3<f32> {
+        if self.keyframes.is_empty() {
+            return Point3::origin();
+        }
+
+        let (segment, t) = self.get_segment();
+        let segment = segment as isize;
+        let p0 = self.get_keyframe(segment - 1).position.coords;
+        let p1 = self.get_keyframe(segment).position.coords;
+        let p2 = self.get_keyframe(segment + 1).position.coords;
+        let p3 = self.get_keyframe(segment + 2).position.coords;
+
+        let t2 = t * t;
+        let t3 = t2 * t;
+        Point3::from(
+            0.5 * (2.0 * p1
+                + (p2 - p0) * t
+                + (2.0 * p0 - 5.0 * p1 + 4.0 * p2 - p3) * t2
+                + (3.0 * p1 - p0 - 3.0 * p2 + p3) * t3),
+        )
+    }
+
+    fn get_direction(&self) -> Vector3<f32> {
+        self.get_orientation() * Vector3::new(0.0, 0.0, 1.0)
+    }
+
+    fn get_up(&self) -> Vector3<f32> {
+        self.get_orientation() * Vector3::new(0.0, 1.0, 0.0)
+    }
+
+    fn update(&mut self, dt: f32) {
+        if !self.playing {
+            return;
+        }
+
+        self.time += dt;
+        if self.time >= self.duration {
+            if self.looping {
+                self.time %= self.duration.max(f32::EPSILON);
+            } else {
+                self.time = self.duration;
+                self.playing = false;
+            }
+        }
+    }
+}
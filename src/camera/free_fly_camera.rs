@@ -0,0 +1,105 @@
+use std::collections::HashSet;
+
+use nalgebra::{clamp, Matrix4, Point3, Vector2, Vector3};
+use winit::event::VirtualKeyCode;
+
+use crate::camera::camera::Camera;
+
+const MAX_PITCH: f32 = 89.0 * std::f32::consts::PI / 180.0;
+
+pub struct FreeFlyCamera {
+    position: Point3<f32>,
+    yaw: f32,
+    pitch: f32,
+    pressed_keys: HashSet<VirtualKeyCode>,
+    pub speed: f32,
+}
+
+impl FreeFlyCamera {
+    pub fn new(position: Point3<f32>, direction: Vector3<f32>) -> Self {
+        let mut camera = Self {
+            position,
+            yaw: 0.0,
+            pitch: 0.0,
+            pressed_keys: HashSet::new(),
+            speed: 2.0,
+        };
+        camera.look_from(position, direction);
+        camera
+    }
+
+    pub fn look_from(&mut self, position: Point3<f32>, direction: Vector3<f32>) {
+        let direction = direction.normalize();
+        self.position = position;
+        self.yaw = direction.x.atan2(direction.z);
+        self.pitch = clamp(direction.y.asin(), -MAX_PITCH, MAX_PITCH);
+    }
+
+    fn get_right(&self) -> Vector3<f32> {
+        self.get_direction()
+            .cross(&Vector3::new(0.0, 1.0, 0.0))
+            .normalize()
+    }
+}
+
+impl Camera for FreeFlyCamera {
+    fn get_view(&self) -> Matrix4<f32> {
+        Matrix4::look_at_rh(
+            &self.position,
+            &(self.position + self.get_direction()),
+            &Vector3::new(0.0, 1.0, 0.0),
+        )
+    }
+
+    fn get_position(&self) -> Point3<f32> {
+        self.position
+    }
+
+    fn get_direction(&self) -> Vector3<f32> {
+        Vector3::new(
+            self.pitch.cos() * self.yaw.sin(),
+            self.pitch.sin(),
+            self.pitch.cos() * self.yaw.cos(),
+        )
+    }
+
+    fn rotate(&mut self, delta: Vector2<f32>) {
+        self.yaw -= delta.x * 0.005;
+        self.pitch = clamp(self.pitch - delta.y * 0.005, -MAX_PITCH, MAX_PITCH);
+    }
+
+    fn zoom(&mut self, delta: f32) {
+        self.speed = clamp(self.speed * (1.0 + delta * 0.1), 0.1, 20.0);
+    }
+
+    fn handle_key(&mut self, key: VirtualKeyCode, pressed: bool) {
+        if pressed {
+            self.pressed_keys.insert(key);
+        } else {
+            self.pressed_keys.remove(&key);
+        }
+    }
+
+    fn update(&mut self, dt: f32) {
+        let forward = self.get_direction();
+        let right = self.get_right();
+        let up = Vector3::new(0.0, 1.0, 0.0);
+
+        let mut movement = Vector3::zeros();
+        for key in self.pressed_keys.iter() {
+            match key {
+                VirtualKeyCode::W => movement += forward,
+                VirtualKeyCode::S => movement -= forward,
+                VirtualKeyCode::D => movement += right,
+                VirtualKeyCode::A => movement -= right,
+                VirtualKeyCode::E => movement += up,
+                VirtualKeyCode::Q => movement -= up,
+                _ => {}
+            }
+        }
+
+        if movement.norm() > f32::EPSILON {
+            self.position += movement.normalize() * self.speed * dt;
+        }
+    }
+}
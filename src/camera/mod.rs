@@ -0,0 +1,10 @@
+pub mod bookmarks;
+#[allow(clippy::module_inception)]
+pub mod camera;
+pub mod cameras;
+pub mod chase_camera;
+pub mod fly_through_camera;
+pub mod free_fly_camera;
+pub mod orbit_camera;
+pub mod pose_camera;
+pub mod projection;
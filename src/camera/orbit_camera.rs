@@ -0,0 +1,118 @@
+use nalgebra::{clamp, Matrix4, Point3, UnitQuaternion, Vector2, Vector3};
+
+use crate::camera::camera::Camera;
+
+const ROTATION_SPEED: f32 = 0.01;
+const ZOOM_SPEED: f32 = 0.1;
+const MIN_VELOCITY: f32 = 0.001;
+
+pub struct OrbitCamera {
+    orientation: UnitQuaternion<f32>,
+    distance: f32,
+    pending_rotation: Vector2<f32>,
+    pending_zoom: f32,
+    rotation_velocity: Vector2<f32>,
+    zoom_velocity: f32,
+    pub target: Point3<f32>,
+    pub min_distance: f32,
+    pub max_distance: f32,
+    pub damping: f32,
+}
+
+impl OrbitCamera {
+    pub fn new(distance: f32) -> Self {
+        Self {
+            orientation: UnitQuaternion::identity(),
+            distance,
+            pending_rotation: Vector2::zeros(),
+            pending_zoom: 0.0,
+            rotation_velocity: Vector2::zeros(),
+            zoom_velocity: 0.0,
+            target: Point3::origin(),
+            min_distance: 0.5,
+            max_distance: 20.0,
+            damping: 6.0,
+        }
+    }
+
+    pub fn get_distance(&self) -> f32 {
+        self.distance
+    }
+
+    pub fn set_distance(&mut self, distance: f32) {
+        self.distance = clamp(distance, self.min_distance, self.max_distance);
+    }
+
+    /// Sets the orientation from a yaw around the vertical axis followed by a pitch,
+    /// both in radians.
+    pub fn set_angles(&mut self, yaw: f32, pitch: f32) {
+        self.orientation = UnitQuaternion::from_axis_angle(&Vector3::y_axis(), yaw)
+            * UnitQuaternion::from_axis_angle(&Vector3::x_axis(), pitch);
+    }
+
+    /// Rotates around the camera's own up and right axes, so the orbit can pass over
+    /// the poles without flipping.
+    fn apply_rotation(&mut self, delta: Vector2<f32>) {
+        let yaw = UnitQuaternion::from_axis_angle(&Vector3::y_axis(), delta.x * ROTATION_SPEED);
+        let pitch = UnitQuaternion::from_axis_angle(&Vector3::x_axis(), delta.y * ROTATION_SPEED);
+        self.orientation = self.orientation * yaw * pitch;
+        self.orientation.renormalize();
+    }
+
+    fn apply_zoom(&mut self, delta: f32) {
+        self.set_distance(self.distance * (-delta * ZOOM_SPEED).exp());
+    }
+}
+
+impl Camera for OrbitCamera {
+    fn get_view(&self) -> Matrix4<f32> {
+        Matrix4::look_at_rh(&self.get_position(), &self.target, &self.get_up())
+    }
+
+    fn get_position(&self) -> Point3<f32> {
+        self.target - self.distance * self.get_direction()
+    }
+
+    fn get_direction(&self) -> Vector3<f32> {
+        self.orientation * Vector3::new(0.0, 0.0, 1.0)
+    }
+
+    fn get_up(&self) -> Vector3<f32> {
+        self.orientation * Vector3::new(0.0, 1.0, 0.0)
+    }
+
+    fn rotate(&mut self, delta: Vector2<f32>) {
+        self.pending_rotation += delta;
+    }
+
+    fn zoom(&mut self, delta: f32) {
+        self.pending_zoom += delta;
+    }
+
+    /// Applies the input gathered since the last frame. Without new input the camera
+    /// keeps moving with its last velocity, which decays with `damping`.
+    fn update(&mut self, dt: f32) {
+        let dt = dt.max(f32::EPSILON);
+        let decay = (-self.damping * dt).exp();
+
+        if self.pending_rotation.norm() > 0.0 {
+            let rotation = self.pending_rotation;
+            self.rotation_velocity = rotation / dt;
+            self.pending_rotation = Vector2::zeros();
+            self.apply_rotation(rotation);
+        } else if self.rotation_velocity.norm() > MIN_VELOCITY {
+            self.rotation_velocity *= decay;
+            self.apply_rotation(self.rotation_velocity * dt);
+        }
+
+        if self.pending_zoom != 0.0 {
+            let zoom = self.pending_zoom;
+            self.zoom_velocity = zoom / dt;
+            self.pending_zoom = 0.0;
+            self.apply_zoom(zoom);
+        } else if self.zoom_velocity.abs() > MIN_VELOCITY {
+            self.zoom_velocity *= decay;
+            self.apply_zoom(self.zoom_velocity * dt);
+        }
+    }
+}
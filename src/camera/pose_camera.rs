@@ -0,0 +1,53 @@
+use nalgebra::{Matrix4, Point3, UnitQuaternion, Vector3};
+
+use crate::camera::bookmarks::Bookmark;
+use crate::camera::camera::Camera;
+
+/// Stays at a recorded pose, keeping its full orientation including roll, so replayed
+/// frames are drawn from exactly where they were recorded.
+pub struct PoseCamera {
+    position: Point3<f32>,
+    orientation: UnitQuaternion<f32>,
+}
+
+impl Default for PoseCamera {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PoseCamera {
+    pub fn new() -> Self {
+        Self {
+            position: Point3::origin(),
+            orientation: UnitQuaternion::identity(),
+        }
+    }
+
+    pub fn set_pose(&mut self, pose: &Bookmark) {
+        self.position = pose.position;
+        self.orientation = pose.orientation;
+    }
+}
+
+impl Camera for PoseCamera {
+    fn get_view(&self) -> Matrix4<f32> {
+        Matrix4::look_at_rh(
+            &self.position,
+            &(self.position + self.get_direction()),
+            &self.get_up(),
+        )
+    }
+
+    fn get_position(&self) -> Point3<f32> {
+        self.position
+    }
+
+    fn get_direction(&self) -> Vector3<f32> {
+        self.orientation * Vector3::new(0.0, 0.0, 1.0)
+    }
+
+    fn get_up(&self) -> Vector3<f32> {
+        self.orientation * Vector3::new(0.0, 1.0, 0.0)
+    }
+}
@@ -0,0 +1,29 @@
+use nalgebra::Matrix4;
+
+pub struct Projection {
+    pub aspect: f32,
+    pub fov: f32,
+    pub near: f32,
+    pub far: f32,
+}
+
+impl Projection {
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            aspect: width as f32 / height as f32,
+            fov: std::f32::consts::PI / 2.0,
+            near: 0.1,
+            far: 100.0,
+        }
+    }
+
+    pub fn resize(&mut self, width: u32, height: u32) {
+        if width > 0 && height > 0 {
+            self.aspect = width as f32 / height as f32;
+        }
+    }
+
+    pub fn get_matrix(&self) -> Matrix4<f32> {
+        Matrix4::new_perspective(self.aspect, self.fov, self.near, self.far)
+    }
+}
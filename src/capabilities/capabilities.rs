@@ -0,0 +1,116 @@
+use glium::backend::Facade;
+use glium::program::ComputeShader;
+use glium::texture::UncompressedFloatFormat;
+use glium::{Api, CapabilitiesSource, Version};
+
+use crate::water::water_normal_computer::is_valid_grid_size;
+
+/// The GLSL version the compute shaders are written in.
+const COMPUTE_GLSL_VERSION: Version = Version(Api::Gl, 4, 3);
+
+/// How the water simulation runs on the current context.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WaterPath {
+    /// Compute shaders writing to float images.
+    Compute,
+    /// Fragment shaders rendering into float textures.
+    Fragment,
+}
+
+/// What the OpenGL context supports, queried once at startup so that every subsystem
+/// can choose the best path available instead of failing on the first missing feature.
+#[derive(Debug, Clone)]
+pub struct Capabilities {
+    pub renderer: String,
+    pub version: String,
+    pub glsl_version: Version,
+    pub compute_shaders: bool,
+    pub float_textures: bool,
+    pub float_render_targets: bool,
+    pub max_texture_size: u32,
+}
+
+impl Capabilities {
+    pub fn detect(display: &impl Facade) -> Self {
+        let context = display.get_context();
+        let glsl_version = context.get_supported_glsl_version();
+        Self {
+            renderer: context.get_opengl_renderer_string().to_string(),
+            version: context.get_opengl_version_string().to_string(),
+            glsl_version,
+            compute_shaders: ComputeShader::is_supported(context.as_ref())
+                && glsl_version >= COMPUTE_GLSL_VERSION,
+            float_textures: UncompressedFloatFormat::F32.is_supported(context.as_ref()),
+            float_render_targets: UncompressedFloatFormat::F32
+                .is_color_renderable(context.as_ref()),
+            max_texture_size: context.get_capabilities().max_texture_size.max(0) as u32,
+        }
+    }
+
+    /// The best water simulation path, or `None` when the context supports neither.
+    pub fn get_water_path(&self) -> Option<WaterPath> {
+        if self.compute_shaders && self.float_textures {
+            Some(WaterPath::Compute)
+        } else if self.float_render_targets {
+            Some(WaterPath::Fragment)
+        } else {
+            None
+        }
+    }
+
+    /// The largest valid water grid size up to `requested` that fits in a texture.
+    pub fn get_grid_size(&self, requested: u32) -> u32 {
+        (1..=requested.min(self.max_texture_size))
+            .rev()
+            .find(|&size| is_valid_grid_size(size))
+            .unwrap_or(requested)
+    }
+
+    /// The largest factor up to `requested` by which a `width` by `height` image can be
+    /// scaled and still fit in a texture.
+    pub fn get_supersampling(&self, width: u32, height: u32, requested: u32) -> u32 {
+        let largest = self.max_texture_size / width.max(height).max(1);
+        requested.min(largest).max(1)
+    }
+
+    /// Describes the context and the path each subsystem takes on it, for a scene
+    /// asking for a `grid_size` water grid.
+    pub fn get_report(&self, grid_size: u32) -> String {
+        let water = match self.get_water_path() {
+            Some(WaterPath::Compute) => "compute shaders",
+            Some(WaterPath::Fragment) => "fragment shaders (no compute shader support)",
+            None => "unavailable (no float render targets)",
+        };
+        let grid = match self.get_grid_size(grid_size) {
+            size if size == grid_size => size.to_string(),
+            size => format!(
+                "{} (limited by the max texture size, {} requested)",
+                size, grid_size
+            ),
+        };
+
+        [
+            format!("OpenGL: {}", self.version),
+            format!("Renderer: {}", self.renderer),
+            format!("GLSL: {}.{}", self.glsl_version.1, self.glsl_version.2),
+            format!("Compute shaders: {}", get_yes_no(self.compute_shaders)),
+            format!("Float textures: {}", get_yes_no(self.float_textures)),
+            format!(
+                "Float render targets: {}",
+                get_yes_no(self.float_render_targets)
+            ),
+            format!("Max texture size: {}", self.max_texture_size),
+            format!("Water simulation: {}", water),
+            format!("Water grid: {}", grid),
+        ]
+        .join("\n")
+    }
+}
+
+fn get_yes_no(supported: bool) -> &'static str {
+    if supported {
+        "yes"
+    } else {
+        "no"
+    }
+}
@@ -0,0 +1,4 @@
+pub mod offscreen_target;
+pub mod png_writer;
+pub mod recorder;
+pub mod screenshot;
@@ -0,0 +1,83 @@
+use std::error::Error;
+use std::path::Path;
+
+use glium::backend::Facade;
+use glium::framebuffer::{DepthRenderBuffer, SimpleFrameBuffer};
+use glium::texture::{DepthFormat, MipmapsOption, RawImage2d, UncompressedFloatFormat};
+use glium::{CapabilitiesSource, Surface, Texture2d};
+use image::ImageResult;
+
+use crate::capture::png_writer::write_png;
+use crate::renderer::frame_context::FrameContext;
+use crate::renderer::render_target::RenderTarget;
+use crate::renderer::renderer::Renderer;
+
+/// A color texture with a depth buffer that the scene can be drawn into instead of the
+/// window.
+pub struct OffscreenTarget {
+    color: Texture2d,
+    depth: DepthRenderBuffer,
+}
+
+impl OffscreenTarget {
+    /// Fails when the context cannot allocate a target of that size, for example one
+    /// larger than its maximum texture size.
+    pub fn new(display: &impl Facade, width: u32, height: u32) -> Result<Self, Box<dyn Error>> {
+        // Checked here because the driver only reports an oversized texture as a debug
+        // message, which glium treats as a bug.
+        let max_size = display
+            .get_context()
+            .get_capabilities()
+            .max_texture_size
+            .max(0) as u32;
+        if width.max(height) > max_size {
+            return Err(format!(
+                "{}x{} is larger than the maximum texture size {}",
+                width, height, max_size
+            )
+            .into());
+        }
+
+        let color = Texture2d::empty_with_format(
+            display,
+            UncompressedFloatFormat::U8U8U8U8,
+            MipmapsOption::NoMipmap,
+            width,
+            height,
+        )?;
+        let depth = DepthRenderBuffer::new(display, DepthFormat::F32, width, height)?;
+
+        Ok(Self { color, depth })
+    }
+
+    pub fn get_framebuffer(&self, display: &impl Facade) -> SimpleFrameBuffer<'_> {
+        SimpleFrameBuffer::with_depth_buffer(display, &self.color, &self.depth).unwrap()
+    }
+
+    /// Clears the target and renders the frame into it.
+    pub fn render(&self, display: &impl Facade, renderer: &Renderer, frame: &FrameContext) {
+        let mut framebuffer = self.get_framebuffer(display);
+        framebuffer.clear_color_and_depth((0.0, 0.0, 0.0, 1.0), 1.0);
+        renderer.render(
+            display,
+            &mut RenderTarget::Framebuffer(&mut framebuffer),
+            frame,
+        );
+    }
+
+    pub fn get_color_texture(&self) -> &Texture2d {
+        &self.color
+    }
+
+    pub fn get_dimensions(&self) -> (u32, u32) {
+        self.color.dimensions()
+    }
+
+    pub fn read(&self) -> RawImage2d<'static, u8> {
+        self.color.read()
+    }
+
+    pub fn save_png(&self, path: &Path) -> ImageResult<()> {
+        write_png(path, self.read())
+    }
+}
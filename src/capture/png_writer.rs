@@ -0,0 +1,16 @@
+use std::path::Path;
+
+use glium::texture::RawImage2d;
+use image::{imageops, ImageResult, RgbaImage};
+
+/// Writes pixels read back from OpenGL, whose rows start at the bottom of the image, as
+/// a PNG file.
+pub fn write_png(path: &Path, raw_image: RawImage2d<u8>) -> ImageResult<()> {
+    let image = RgbaImage::from_raw(
+        raw_image.width,
+        raw_image.height,
+        raw_image.data.into_owned(),
+    )
+    .unwrap();
+    imageops::flip_vertical(&image).save(path)
+}
@@ -0,0 +1,129 @@
+use std::error::Error;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+
+use chrono::Local;
+use glium::backend::Facade;
+
+use crate::capture::offscreen_target::OffscreenTarget;
+use crate::capture::png_writer::write_png;
+use crate::renderer::frame_context::FrameContext;
+use crate::renderer::renderer::Renderer;
+
+/// Records every rendered frame into a numbered PNG sequence and, when ffmpeg can be
+/// started, into an MP4 video. Frames have a fixed size and are meant to be produced
+/// at a fixed simulation rate, so the result does not depend on rendering speed.
+pub struct Recorder {
+    directory: PathBuf,
+    target: OffscreenTarget,
+    frame_rate: u32,
+    frame: u32,
+    ffmpeg: Option<Child>,
+}
+
+impl Recorder {
+    pub fn start(
+        display: &impl Facade,
+        output_directory: &Path,
+        width: u32,
+        height: u32,
+        frame_rate: u32,
+        use_ffmpeg: bool,
+    ) -> Result<Self, Box<dyn Error>> {
+        let directory = output_directory.join(format!(
+            "recording_{}",
+            Local::now().format("%Y%m%d_%H%M%S")
+        ));
+        fs::create_dir_all(&directory)?;
+
+        let ffmpeg = if use_ffmpeg {
+            spawn_ffmpeg(&directory, width, height, frame_rate)
+        } else {
+            None
+        };
+
+        Ok(Self {
+            directory,
+            target: OffscreenTarget::new(display, width, height)?,
+            frame_rate,
+            frame: 0,
+            ffmpeg,
+        })
+    }
+
+    pub fn get_frame_dt(&self) -> f32 {
+        1.0 / self.frame_rate as f32
+    }
+
+    pub fn get_frame_count(&self) -> u32 {
+        self.frame
+    }
+
+    pub fn get_directory(&self) -> &Path {
+        &self.directory
+    }
+
+    pub fn is_encoding(&self) -> bool {
+        self.ffmpeg.is_some()
+    }
+
+    pub fn record(
+        &mut self,
+        display: &impl Facade,
+        renderer: &Renderer,
+        frame: &FrameContext,
+    ) -> Result<(), Box<dyn Error>> {
+        self.target.render(display, renderer, frame);
+        let raw_image = self.target.read();
+
+        if let Some(stdin) = self
+            .ffmpeg
+            .as_mut()
+            .and_then(|ffmpeg| ffmpeg.stdin.as_mut())
+        {
+            stdin.write_all(&raw_image.data)?;
+        }
+
+        let path = self.directory.join(format!("frame_{:05}.png", self.frame));
+        write_png(&path, raw_image)?;
+        self.frame += 1;
+        Ok(())
+    }
+
+    /// Closes the ffmpeg input and waits for the video to be written.
+    pub fn finish(mut self) -> Result<PathBuf, Box<dyn Error>> {
+        if let Some(mut ffmpeg) = self.ffmpeg.take() {
+            drop(ffmpeg.stdin.take());
+            let status = ffmpeg.wait()?;
+            if !status.success() {
+                return Err(format!("ffmpeg exited with {}", status).into());
+            }
+        }
+        Ok(self.directory)
+    }
+}
+
+fn spawn_ffmpeg(directory: &Path, width: u32, height: u32, frame_rate: u32) -> Option<Child> {
+    Command::new("ffmpeg")
+        .args([
+            "-y",
+            "-f",
+            "rawvideo",
+            "-pixel_format",
+            "rgba",
+            "-video_size",
+        ])
+        .arg(format!("{}x{}", width, height))
+        .arg("-framerate")
+        .arg(frame_rate.to_string())
+        .args(["-i", "-", "-vf", "vflip,scale=trunc(iw/2)*2:trunc(ih/2)*2"])
+        .args(["-pix_fmt", "yuv420p"])
+        .arg(directory.join("recording.mp4"))
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .ok()
+}
@@ -0,0 +1,70 @@
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use chrono::Local;
+use glium::backend::Facade;
+use glium::glutin::surface::WindowSurface;
+use glium::texture::RawImage2d;
+use glium::Display;
+
+use crate::capture::offscreen_target::OffscreenTarget;
+use crate::capture::png_writer::write_png;
+use crate::renderer::frame_context::FrameContext;
+use crate::renderer::renderer::Renderer;
+
+pub struct ScreenshotSettings {
+    pub supersampling: u32,
+    pub include_overlay: bool,
+}
+
+impl Default for ScreenshotSettings {
+    fn default() -> Self {
+        Self {
+            supersampling: 1,
+            include_overlay: true,
+        }
+    }
+}
+
+impl ScreenshotSettings {
+    /// The overlay is only part of the window's framebuffer, so it can only be kept in
+    /// screenshots taken at the window resolution.
+    pub fn reads_window(&self) -> bool {
+        self.supersampling == 1 && self.include_overlay
+    }
+}
+
+pub fn get_screenshot_path(directory: &Path) -> Result<PathBuf, Box<dyn Error>> {
+    fs::create_dir_all(directory)?;
+    let file_name = format!(
+        "screenshot_{}.png",
+        Local::now().format("%Y%m%d_%H%M%S_%3f")
+    );
+    Ok(directory.join(file_name))
+}
+
+pub fn save_window_screenshot(
+    display: &Display<WindowSurface>,
+    path: &Path,
+) -> Result<(), Box<dyn Error>> {
+    let raw_image: RawImage2d<u8> = display.read_front_buffer()?;
+    write_png(path, raw_image)?;
+    Ok(())
+}
+
+/// Renders the pool into an offscreen target of the given size, without the overlay,
+/// and saves it.
+pub fn save_offscreen_screenshot(
+    display: &impl Facade,
+    renderer: &Renderer,
+    frame: &FrameContext,
+    width: u32,
+    height: u32,
+    path: &Path,
+) -> Result<(), Box<dyn Error>> {
+    let target = OffscreenTarget::new(display, width, height)?;
+    target.render(display, renderer, frame);
+    target.save_png(path)?;
+    Ok(())
+}
@@ -0,0 +1,146 @@
+use std::path::PathBuf;
+
+use crate::water::water_normal_computer::is_valid_grid_size;
+
+pub const USAGE: &str = "\
+Usage: duck [OPTIONS]
+
+Options:
+  --width <pixels>        Window or frame width [default: 800]
+  --height <pixels>       Window or frame height [default: 600]
+  --fullscreen            Open a borderless fullscreen window
+  --vsync <on|off>        Synchronize presentation with the display [default: on]
+  --scene <file>          Load the scene from a TOML file
+  --assets <directory>    Look up textures and meshes in this directory first,
+                          then next to the executable and in the working
+                          directory
+  --shaders-from-disk     Load shaders from the asset directories instead of
+                          the copies built into the executable
+  --watch                 Reload changed textures, meshes and shaders while
+                          running; implies --shaders-from-disk
+  --seed <number>         Seed the simulation [default: random]
+  --grid <cells>          Water simulation grid resolution, a multiple of 8
+                          between 64 and 2048 [default: from the scene, 256]
+  --headless <frames>     Render frames to PNG files without opening a window
+  --output <directory>    Directory for frames, screenshots and recordings
+                          [default: output]
+  --replay <file>         Replay recorded inputs
+  -h, --help              Print this help";
+
+pub struct Options {
+    pub width: u32,
+    pub height: u32,
+    pub fullscreen: bool,
+    pub vsync: bool,
+    pub grid_size: Option<u32>,
+    pub headless_frames: Option<u32>,
+    pub output_directory: PathBuf,
+    pub seed: u64,
+    pub replay_file: Option<PathBuf>,
+    pub scene_file: Option<PathBuf>,
+    pub asset_root: Option<PathBuf>,
+    pub shaders_from_disk: bool,
+    pub watch: bool,
+    pub help: bool,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            width: 800,
+            height: 600,
+            fullscreen: false,
+            vsync: true,
+            grid_size: None,
+            headless_frames: None,
+            output_directory: PathBuf::from("output"),
+            seed: rand::random(),
+            replay_file: None,
+            scene_file: None,
+            asset_root: None,
+            shaders_from_disk: false,
+            watch: false,
+            help: false,
+        }
+    }
+}
+
+impl Options {
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Self, String> {
+        let mut options = Self::default();
+        let mut args = args.into_iter().skip(1);
+        while let Some(arg) = args.next() {
+            let mut value = || args.next().ok_or(format!("missing value for {}", arg));
+            match arg.as_str() {
+                "--width" => options.width = parse_size(&arg, value()?)?,
+                "--height" => options.height = parse_size(&arg, value()?)?,
+                "--fullscreen" => options.fullscreen = true,
+                "--vsync" => {
+                    options.vsync = match value()?.as_str() {
+                        "on" => true,
+                        "off" => false,
+                        other => return Err(format!("invalid value {} for {}", other, arg)),
+                    }
+                }
+                "--grid" => {
+                    let size = parse_value(&arg, value()?)?;
+                    if !is_valid_grid_size(size) {
+                        return Err(format!(
+                            "invalid value {} for {}: must be a multiple of 8 between 64 and 2048",
+                            size, arg
+                        ));
+                    }
+                    options.grid_size = Some(size);
+                }
+                "--headless" => options.headless_frames = Some(parse_value(&arg, value()?)?),
+                "--output" => options.output_directory = PathBuf::from(value()?),
+                "--seed" => options.seed = parse_value(&arg, value()?)?,
+                "--replay" => options.replay_file = Some(PathBuf::from(value()?)),
+                "--scene" => options.scene_file = Some(PathBuf::from(value()?)),
+                "--assets" => options.asset_root = Some(PathBuf::from(value()?)),
+                "--shaders-from-disk" => options.shaders_from_disk = true,
+                "--watch" => {
+                    options.watch = true;
+                    options.shaders_from_disk = true;
+                }
+                "-h" | "--help" => options.help = true,
+                _ => return Err(format!("unknown argument {}", arg)),
+            }
+        }
+        Ok(options)
+    }
+}
+
+fn parse_value<T: std::str::FromStr>(arg: &str, value: String) -> Result<T, String> {
+    value
+        .parse::<T>()
+        .map_err(|_| format!("invalid value {} for {}", value, arg))
+}
+
+fn parse_size(arg: &str, value: String) -> Result<u32, String> {
+    match parse_value(arg, value)? {
+        0 => Err(format!("{} must be positive", arg)),
+        size => Ok(size),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Options, String> {
+        Options::parse(["duck"].iter().chain(args).map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn zero_size_is_rejected() {
+        assert!(parse(&["--width", "0"]).is_err());
+        assert!(parse(&["--height", "0"]).is_err());
+    }
+
+    #[test]
+    fn size_is_parsed() {
+        let options = parse(&["--width", "320", "--height", "240"]).unwrap();
+        assert_eq!((options.width, options.height), (320, 240));
+    }
+}
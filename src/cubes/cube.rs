@@ -1,4 +1,4 @@
-use glium::glutin::surface::WindowSurface;
+use glium::backend::Facade;
 use glium::{IndexBuffer, VertexBuffer};
 
 use crate::cubes::vertex::Vertex;
@@ -10,7 +10,7 @@ pub struct Cube {
 }
 
 impl Cube {
-    pub fn new(display: &glium::Display<WindowSurface>) -> Self {
+    pub fn new(display: &impl Facade) -> Self {
         let vertices = vec![
             // back
             Vertex::new([-1.0, -1.0, -1.0], [0.0, 0.0, 1.0], [0.0, 1.0], 0),
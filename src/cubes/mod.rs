@@ -1,3 +1,3 @@
 pub mod cube;
 pub mod cube_drawer;
-mod vertex;
+pub mod vertex;
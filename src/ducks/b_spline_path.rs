@@ -0,0 +1,148 @@
+use std::collections::VecDeque;
+use std::f32::consts::TAU;
+
+use nalgebra::{clamp, Point2, Vector2};
+use rand::Rng;
+
+pub struct BSplinePath {
+    pub control_points: [Point2<f32>; 4],
+    pub pending_points: VecDeque<Point2<f32>>,
+    pub t: f32,
+    pub range: f32,
+}
+
+impl BSplinePath {
+    /// Creates a path from `start` that wanders randomly within `range` of the origin
+    /// on both axes.
+    pub fn new(start: Point2<f32>, range: f32, rng: &mut impl Rng) -> Self {
+        let p1 = get_random_point(start, range, rng);
+        let p2 = get_random_point(p1, range, rng);
+        let p3 = get_random_point(p2, range, rng);
+
+        Self {
+            control_points: [start, p1, p2, p3],
+            pending_points: VecDeque::new(),
+            t: 0.0,
+            range,
+        }
+    }
+
+    /// Creates a path that starts exactly at `start`, passes near each of `waypoints` in
+    /// order and then wanders randomly within `range` of the origin.
+    pub fn through(
+        start: Point2<f32>,
+        waypoints: &[Point2<f32>],
+        range: f32,
+        rng: &mut impl Rng,
+    ) -> Self {
+        let mut pending_points: VecDeque<_> = waypoints.iter().copied().collect();
+        let p2 = pending_points
+            .pop_front()
+            .unwrap_or_else(|| get_random_point(start, range, rng));
+        let p3 = pending_points
+            .pop_front()
+            .unwrap_or_else(|| get_random_point(p2, range, rng));
+
+        // Mirroring the first point around the start puts the curve at `start` at t = 0.
+        Self {
+            control_points: [start - (p2 - start), start, p2, p3],
+            pending_points,
+            t: 0.0,
+            range,
+        }
+    }
+
+    pub fn advance(&mut self, dt: f32, rng: &mut impl Rng) {
+        self.t += dt;
+
+        if self.t >= 1.0 {
+            self.t = 0.0;
+            self.control_points = [
+                self.control_points[1],
+                self.control_points[2],
+                self.control_points[3],
+                self.pending_points
+                    .pop_front()
+                    .unwrap_or_else(|| get_random_point(self.control_points[3], self.range, rng)),
+            ];
+        }
+    }
+
+    /// Re-plans the path so that it starts at the current position with the current
+    /// derivative (keeping the path C1 continuous) and comes to a stop at `destination`.
+    pub fn steer_to(&mut self, destination: Point2<f32>) {
+        let position = self.get_position();
+        let direction = self.get_direction();
+
+        self.control_points = [
+            position - direction,
+            position,
+            position + direction,
+            destination,
+        ];
+        self.pending_points = VecDeque::from([destination, destination]);
+        self.t = 0.0;
+    }
+
+    pub fn get_destination(&self) -> Option<Point2<f32>> {
+        self.pending_points.back().copied()
+    }
+
+    pub fn get_position(&self) -> Point2<f32> {
+        get_b_spline_value(self.control_points, self.t)
+    }
+
+    pub fn get_direction(&self) -> Vector2<f32> {
+        get_b_spline_derivative_value(self.control_points, self.t)
+    }
+}
+
+fn get_random_point(p0: Point2<f32>, range: f32, rng: &mut impl Rng) -> Point2<f32> {
+    let angle = rng.gen_range(0.0..TAU);
+    let direction = Vector2::new(angle.cos(), angle.sin());
+    Point2::new(
+        clamp(p0.x + direction.x * range, -range, range),
+        clamp(p0.y + direction.y * range, -range, range),
+    )
+}
+
+pub fn get_b_spline_value(b_spline: [Point2<f32>; 4], t: f32) -> Point2<f32> {
+    let t2 = t * t;
+    let t3 = t2 * t;
+    let b0 = (-t3 + 3.0 * t2 - 3.0 * t + 1.0) / 6.0;
+    let b1 = (3.0 * t3 - 6.0 * t2 + 4.0) / 6.0;
+    let b2 = (-3.0 * t3 + 3.0 * t2 + 3.0 * t + 1.0) / 6.0;
+    let b3 = t3 / 6.0;
+    Point2::new(
+        b_spline[0].x * b0 + b_spline[1].x * b1 + b_spline[2].x * b2 + b_spline[3].x * b3,
+        b_spline[0].y * b0 + b_spline[1].y * b1 + b_spline[2].y * b2 + b_spline[3].y * b3,
+    )
+}
+
+pub fn get_b_spline_derivative_value(b_spline: [Point2<f32>; 4], t: f32) -> Vector2<f32> {
+    const EPS: f32 = 0.0001;
+    let t1 = t - EPS;
+    let t2 = t + EPS;
+    let p1 = get_b_spline_value(b_spline, t1);
+    let p2 = get_b_spline_value(b_spline, t2);
+    (p2.coords - p1.coords) / (2.0 * EPS)
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    use super::*;
+
+    #[test]
+    fn random_points_are_finite_and_in_range() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let mut point = Point2::origin();
+        for _ in 0..100_000 {
+            point = get_random_point(point, 3.0, &mut rng);
+            assert!(point.x.is_finite() && point.y.is_finite());
+            assert!(point.x.abs() <= 3.0 && point.y.abs() <= 3.0);
+        }
+    }
+}
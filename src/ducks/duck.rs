@@ -0,0 +1,89 @@
+use nalgebra::{Matrix4, Point2, Vector2, Vector3};
+use rand::Rng;
+
+use crate::ducks::b_spline_path::BSplinePath;
+
+const BASE_SCALE: f32 = 0.005;
+const BASE_RADIUS: f32 = 0.5;
+const MIN_HEADING_SPEED: f32 = 0.001;
+
+pub struct Duck {
+    pub path: BSplinePath,
+    pub position: Point2<f32>,
+    pub offset: Vector2<f32>,
+    pub heading: Vector2<f32>,
+    pub speed: f32,
+    pub scale: f32,
+    pub tint: [f32; 3],
+    /// The index of the mesh and texture the duck is drawn with.
+    pub appearance: usize,
+}
+
+impl Duck {
+    pub fn new(
+        path: BSplinePath,
+        speed: f32,
+        scale: f32,
+        tint: [f32; 3],
+        appearance: usize,
+    ) -> Self {
+        let mut duck = Self {
+            position: path.get_position(),
+            path,
+            offset: Vector2::zeros(),
+            heading: Vector2::new(1.0, 0.0),
+            speed,
+            scale,
+            tint,
+            appearance,
+        };
+        duck.update_heading();
+        duck
+    }
+
+    /// Creates a duck with a random path, speed, scale and tint, drawn with the first
+    /// appearance.
+    pub fn new_random(range: f32, rng: &mut impl Rng) -> Self {
+        let start = Point2::new(rng.gen_range(-range..range), rng.gen_range(-range..range));
+        let speed = rng.gen_range(0.005..0.015);
+        let scale = rng.gen_range(0.004..0.006);
+        let tint = [
+            rng.gen_range(0.6..1.0),
+            rng.gen_range(0.6..1.0),
+            rng.gen_range(0.6..1.0),
+        ];
+        let path = BSplinePath::new(start, range, rng);
+        Self::new(path, speed, scale, tint, 0)
+    }
+
+    pub fn update_heading(&mut self) {
+        let direction = self.path.get_direction();
+        if direction.norm() > MIN_HEADING_SPEED {
+            self.heading = direction;
+        }
+    }
+
+    pub fn get_radius(&self) -> f32 {
+        BASE_RADIUS * self.scale / BASE_SCALE
+    }
+
+    /// Returns the model matrix of the duck floating on water at the height `water_level`
+    /// in world units.
+    pub fn get_model_matrix(&self, water_level: f32) -> Matrix4<f32> {
+        Matrix4::new_translation(&Vector3::new(
+            self.position.x,
+            -0.1 * self.scale / BASE_SCALE + water_level,
+            self.position.y,
+        )) * get_rotation(self.heading)
+            * Matrix4::new_scaling(self.scale)
+    }
+}
+
+fn get_rotation(direction: Vector2<f32>) -> Matrix4<f32> {
+    let c = (direction.x * direction.x + direction.y * direction.y).sqrt();
+    let cos = -direction.x / c;
+    let sin = direction.y / c;
+    Matrix4::new(
+        cos, 0.0, sin, 0.0, 0.0, 1.0, 0.0, 0.0, -sin, 0.0, cos, 0.0, 0.0, 0.0, 0.0, 1.0,
+    )
+}
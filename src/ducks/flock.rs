@@ -0,0 +1,185 @@
+use nalgebra::{clamp, Point2};
+use rand::Rng;
+
+use crate::ducks::b_spline_path::BSplinePath;
+use crate::ducks::duck::Duck;
+use crate::meshes::instance::{InstanceModel, InstanceTint};
+use crate::scene::scene::ObjectDescription;
+use crate::water::water_simulation::WaterSimulation;
+
+const OFFSET_DECAY: f32 = 0.98;
+/// How far from the center, relative to the pool scale, ducks may wander and swim.
+const WANDER_RANGE: f32 = 0.6;
+/// How far from the center, relative to the pool scale, ducks are kept.
+pub const POOL_LIMIT: f32 = 0.9;
+
+pub struct Flock {
+    pub ducks: Vec<Duck>,
+    range: f32,
+    limit: f32,
+}
+
+impl Flock {
+    /// Creates the flock for a pool of the given scale: one duck for each of `objects`,
+    /// drawn with the appearance after its index, followed by `count` random ducks.
+    pub fn new(
+        objects: &[ObjectDescription],
+        count: usize,
+        pool_scale: f32,
+        rng: &mut impl Rng,
+    ) -> Self {
+        let range = WANDER_RANGE * pool_scale;
+        let mut ducks = vec![];
+        for (i, object) in objects.iter().enumerate() {
+            let waypoints: Vec<_> = object.waypoints.iter().copied().map(Point2::from).collect();
+            let path = BSplinePath::through(Point2::from(object.start), &waypoints, range, rng);
+            ducks.push(Duck::new(
+                path,
+                object.speed,
+                object.scale,
+                object.tint,
+                i + 1,
+            ));
+        }
+
+        let mut flock = Self {
+            ducks,
+            range,
+            limit: POOL_LIMIT * pool_scale,
+        };
+        flock.resize(objects.len() + count, rng);
+        flock
+    }
+
+    pub fn resize(&mut self, count: usize, rng: &mut impl Rng) {
+        self.ducks.truncate(count);
+        while self.ducks.len() < count {
+            self.ducks.push(Duck::new_random(self.range, rng));
+        }
+    }
+
+    /// Moves the ducks on by `steps` reference steps, so that they swim at the same
+    /// speed whatever the length of the step the caller takes.
+    pub fn update(&mut self, steps: f32, rng: &mut impl Rng) {
+        let offset_decay = OFFSET_DECAY.powf(steps);
+        for duck in self.ducks.iter_mut() {
+            duck.path.advance(duck.speed * steps, rng);
+            duck.update_heading();
+            duck.offset *= offset_decay;
+            duck.position = duck.path.get_position() + duck.offset;
+        }
+
+        self.separate();
+    }
+
+    pub fn bend_water(&self, water_simulation: &WaterSimulation, pool_scale: f32) {
+        for duck in self.ducks.iter() {
+            water_simulation.bend_at(duck.position, pool_scale, 1.0);
+        }
+    }
+
+    pub fn steer(&mut self, index: usize, destination: Point2<f32>) {
+        if let Some(duck) = self.ducks.get_mut(index) {
+            duck.path.steer_to(Point2::new(
+                clamp(destination.x, -self.limit, self.limit),
+                clamp(destination.y, -self.limit, self.limit),
+            ));
+        }
+    }
+
+    /// The model matrices of the ducks drawn with `appearance`.
+    pub fn get_models(&self, water_level: f32, appearance: usize) -> Vec<InstanceModel> {
+        self.ducks
+            .iter()
+            .filter(|duck| duck.appearance == appearance)
+            .map(|duck| InstanceModel::new(duck.get_model_matrix(water_level)))
+            .collect()
+    }
+
+    /// The tints of the ducks drawn with `appearance`, in the order of `get_models`.
+    pub fn get_tints(&self, appearance: usize) -> Vec<InstanceTint> {
+        self.ducks
+            .iter()
+            .filter(|duck| duck.appearance == appearance)
+            .map(|duck| InstanceTint::new(duck.tint))
+            .collect()
+    }
+
+    fn separate(&mut self) {
+        for i in 0..self.ducks.len() {
+            for j in (i + 1)..self.ducks.len() {
+                let difference = self.ducks[j].position - self.ducks[i].position;
+                let distance = difference.norm();
+                let min_distance = self.ducks[i].get_radius() + self.ducks[j].get_radius();
+                // A non-finite distance would spread to every duck it is compared with.
+                if !distance.is_finite() || distance >= min_distance || distance < f32::EPSILON {
+                    continue;
+                }
+
+                let push = difference / distance * (min_distance - distance) / 2.0;
+                self.ducks[i].offset -= push;
+                self.ducks[i].position -= push;
+                self.ducks[j].offset += push;
+                self.ducks[j].position += push;
+            }
+        }
+
+        for duck in self.ducks.iter_mut() {
+            let clamped = Point2::new(
+                clamp(duck.position.x, -self.limit, self.limit),
+                clamp(duck.position.y, -self.limit, self.limit),
+            );
+            duck.offset += clamped - duck.position;
+            duck.position = clamped;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    use super::*;
+
+    /// The positions of every duck after each of `steps` updates.
+    fn get_trajectories(seed: u64, steps: usize) -> Vec<Vec<Point2<f32>>> {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let objects = [ObjectDescription::default()];
+        let mut flock = Flock::new(&objects, 7, 5.0, &mut rng);
+        (0..steps)
+            .map(|_| {
+                flock.update(1.0, &mut rng);
+                flock.ducks.iter().map(|duck| duck.position).collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn same_seed_gives_same_trajectories() {
+        assert_eq!(get_trajectories(7, 500), get_trajectories(7, 500));
+    }
+
+    #[test]
+    fn different_seeds_give_different_trajectories() {
+        assert_ne!(get_trajectories(7, 500), get_trajectories(8, 500));
+    }
+
+    #[test]
+    fn objects_start_at_their_start_and_head_for_their_waypoints() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let objects = [ObjectDescription {
+            start: [1.0, -1.0],
+            waypoints: vec![[2.0, 2.0], [-2.0, 2.0], [-2.0, -2.0]],
+            ..Default::default()
+        }];
+        let flock = Flock::new(&objects, 0, 5.0, &mut rng);
+        let path = &flock.ducks[0].path;
+        assert!((flock.ducks[0].position - Point2::new(1.0, -1.0)).norm() < 1e-6);
+        assert_eq!(
+            path.control_points[2..],
+            [Point2::new(2.0, 2.0), Point2::new(-2.0, 2.0)]
+        );
+        assert_eq!(path.pending_points, [Point2::new(-2.0, -2.0)]);
+    }
+}
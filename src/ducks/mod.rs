@@ -0,0 +1,3 @@
+pub mod b_spline_path;
+pub mod duck;
+pub mod flock;
@@ -0,0 +1,86 @@
+use std::cell::Cell;
+use std::error::Error;
+use std::ffi::{c_void, CString};
+use std::rc::Rc;
+
+use glium::backend::{Backend, Context};
+use glium::debug::DebugCallbackBehavior;
+use glium::glutin::api::egl::context::PossiblyCurrentContext;
+use glium::glutin::api::egl::device::Device;
+use glium::glutin::api::egl::display::Display;
+use glium::glutin::config::{ConfigSurfaceTypes, ConfigTemplateBuilder};
+use glium::glutin::context::{ContextApi, ContextAttributesBuilder, GlProfile, Version};
+use glium::glutin::prelude::*;
+use glium::SwapBuffersError;
+
+/// OpenGL versions to request, from the most to the least capable.
+const CONTEXT_VERSIONS: [(u8, u8); 4] = [(4, 6), (4, 5), (4, 3), (3, 3)];
+
+/// A glium backend for an EGL context that is made current without any surface, so
+/// that rendering works without a window or a display server.
+struct HeadlessBackend {
+    display: Display,
+    context: PossiblyCurrentContext,
+    dimensions: Cell<(u32, u32)>,
+}
+
+unsafe impl Backend for HeadlessBackend {
+    fn swap_buffers(&self) -> Result<(), SwapBuffersError> {
+        Ok(())
+    }
+
+    unsafe fn get_proc_address(&self, symbol: &str) -> *const c_void {
+        let symbol = CString::new(symbol).unwrap();
+        self.display.get_proc_address(&symbol)
+    }
+
+    fn get_framebuffer_dimensions(&self) -> (u32, u32) {
+        self.dimensions.get()
+    }
+
+    fn resize(&self, new_size: (u32, u32)) {
+        self.dimensions.set(new_size);
+    }
+
+    fn is_current(&self) -> bool {
+        self.context.is_current()
+    }
+
+    unsafe fn make_current(&self) {
+        self.context.make_current_surfaceless().unwrap();
+    }
+}
+
+pub fn create_headless_context(width: u32, height: u32) -> Result<Rc<Context>, Box<dyn Error>> {
+    let device = Device::query_devices()?
+        .next()
+        .ok_or("no EGL device is available")?;
+    let display = unsafe { Display::with_device(&device, None)? };
+
+    let template = ConfigTemplateBuilder::new()
+        .with_surface_type(ConfigSurfaceTypes::empty())
+        .build();
+    let config = unsafe { display.find_configs(template)? }
+        .next()
+        .ok_or("no EGL config is available")?;
+
+    let context = CONTEXT_VERSIONS
+        .iter()
+        .find_map(|&(major, minor)| {
+            let attributes = ContextAttributesBuilder::new()
+                .with_profile(GlProfile::Core)
+                .with_context_api(ContextApi::OpenGl(Some(Version::new(major, minor))))
+                .build(None);
+            unsafe { display.create_context(&config, &attributes) }.ok()
+        })
+        .ok_or("no OpenGL core context could be created")?
+        .make_current_surfaceless()?;
+
+    let backend = HeadlessBackend {
+        display,
+        context,
+        dimensions: Cell::new((width, height)),
+    };
+
+    Ok(unsafe { Context::new(backend, true, DebugCallbackBehavior::default())? })
+}
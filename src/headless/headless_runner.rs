@@ -0,0 +1,85 @@
+use std::error::Error;
+use std::fs;
+
+use crate::assets::asset_manager::AssetManager;
+use crate::assets::asset_paths::AssetPaths;
+use crate::camera::cameras::Cameras;
+use crate::capabilities::capabilities::Capabilities;
+use crate::capture::offscreen_target::OffscreenTarget;
+use crate::cli::options::Options;
+use crate::headless::headless_context::create_headless_context;
+use crate::pool::pool::Pool;
+use crate::renderer::frame_context::FrameContext;
+use crate::renderer::renderer::Renderer;
+use crate::replay::input_recording::read_input_recording;
+use crate::scene::scene::Scene;
+
+const FRAME_DT: f32 = 1.0 / 60.0;
+
+/// Renders `frames` frames of the pool without opening a window and writes them as
+/// numbered PNG files into the output directory. When replaying an input recording,
+/// its seed, size, scene and grid size are used and at most the recorded frames are
+/// rendered.
+pub fn run_headless(options: &Options, scene: &Scene, frames: u32) -> Result<(), Box<dyn Error>> {
+    let replay = match &options.replay_file {
+        Some(path) => Some(read_input_recording(path)?),
+        None => None,
+    };
+    let (seed, width, height, frames) = match &replay {
+        Some(recording) => (
+            recording.seed,
+            recording.width,
+            recording.height,
+            frames.min(recording.frames.len() as u32),
+        ),
+        None => (options.seed, options.width, options.height, frames),
+    };
+    let scene = match &replay {
+        Some(recording) => &recording.get_scene(options.scene_file.as_deref(), scene)?,
+        None => scene,
+    };
+
+    let context = create_headless_context(width, height)?;
+    let capabilities = Capabilities::detect(&context);
+    println!("{}", capabilities.get_report(scene.water.grid_size));
+    println!(
+        "Rendering {} frames headless with {} (seed {})",
+        frames,
+        context.get_opengl_renderer_string(),
+        seed
+    );
+
+    fs::create_dir_all(&options.output_directory)?;
+
+    let mut assets = AssetManager::new(
+        AssetPaths::new(options.asset_root.as_deref()),
+        options.shaders_from_disk,
+    );
+    let mut pool = Pool::new(&context, scene, &mut assets, &capabilities, seed)?;
+    let mut renderer = Renderer::new(&context, &mut assets)?;
+    let mut cameras = Cameras::new(width, height, &scene.camera);
+    let target = OffscreenTarget::new(&context, width, height)?;
+
+    for frame in 0..frames {
+        match &replay {
+            Some(recording) => {
+                let input = &recording.frames[frame as usize];
+                input.apply(&mut pool, &mut renderer, &mut cameras);
+                pool.update(input.dt);
+            }
+            None => {
+                pool.update(FRAME_DT);
+                cameras.get_active_mut().update(FRAME_DT);
+            }
+        }
+
+        target.render(&context, &renderer, &FrameContext::new(&pool, &cameras));
+
+        let path = options
+            .output_directory
+            .join(format!("frame_{:05}.png", frame));
+        target.save_png(&path)?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,2 @@
+pub mod headless_context;
+pub mod headless_runner;
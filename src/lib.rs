@@ -0,0 +1,65 @@
+//! The duck pond simulation: water waves solved on the GPU, ducks swimming on B-spline
+//! paths and the renderer drawing them, usable from the `duck` binary and other tools.
+//!
+//! The runners open a window or render frames headless from parsed options and a
+//! scene. A tool driving its own loop creates a `Pool` and a `Renderer` with an
+//! `AssetManager` and draws `FrameContext`s seen by `Cameras`, adding its own
+//! `RenderPass`es to the renderer's list if it needs to. The modules stay public
+//! so that tools can also reuse parts such as the water simulation or the mesh loader
+//! on their own. The crate root re-exports every type in the signatures of that API.
+
+pub mod assets;
+pub mod camera;
+pub mod capabilities;
+pub mod capture;
+pub mod cli;
+pub mod cubes;
+pub mod ducks;
+pub mod headless;
+pub mod meshes;
+pub mod picking;
+pub mod pool;
+pub mod renderer;
+pub mod replay;
+pub mod scene;
+pub mod water;
+pub mod windowed;
+
+pub use assets::asset_manager::AssetManager;
+pub use assets::asset_paths::AssetPaths;
+pub use assets::shader_error::{ShaderError, ShaderStage};
+pub use assets::shader_preprocessor::PreprocessedShader;
+pub use camera::bookmarks::Bookmark;
+pub use camera::camera::Camera;
+pub use camera::cameras::{CameraMode, Cameras};
+pub use camera::chase_camera::ChaseCamera;
+pub use camera::fly_through_camera::FlyThroughCamera;
+pub use camera::free_fly_camera::FreeFlyCamera;
+pub use camera::orbit_camera::OrbitCamera;
+pub use camera::pose_camera::PoseCamera;
+pub use camera::projection::Projection;
+pub use capabilities::capabilities::{Capabilities, WaterPath};
+pub use cli::options::{Options, USAGE};
+pub use ducks::b_spline_path::BSplinePath;
+pub use ducks::duck::Duck;
+pub use ducks::flock::Flock;
+pub use headless::headless_runner::run_headless;
+pub use meshes::appearance::Appearance;
+pub use meshes::instance::{InstanceModel, InstanceTint};
+pub use meshes::mesh::Mesh;
+pub use meshes::parse_mesh::parse_mesh;
+pub use pool::pool::Pool;
+pub use pool::pool_error::PoolError;
+pub use renderer::frame_context::{EnvironmentTextures, FrameContext};
+pub use renderer::render_pass::RenderPass;
+pub use renderer::render_target::RenderTarget;
+pub use renderer::renderer::Renderer;
+pub use scene::read_scene::read_scene;
+pub use scene::scene::{
+    CameraDescription, DuckDescription, EnvironmentDescription, ObjectDescription, PoolDescription,
+    Scene, WaterDescription,
+};
+pub use water::water_normal_computer::WaterNormalComputer;
+pub use water::water_normal_renderer::WaterNormalRenderer;
+pub use water::water_simulation::WaterSimulation;
+pub use windowed::windowed_runner::run_windowed;
@@ -0,0 +1,28 @@
+use std::path::Path;
+use std::rc::Rc;
+
+use glium::backend::Facade;
+use glium::Texture2d;
+
+use crate::assets::asset_manager::AssetManager;
+use crate::meshes::mesh::Mesh;
+
+/// The mesh and texture a group of floating objects is drawn with.
+pub struct Appearance {
+    pub mesh: Rc<Mesh>,
+    pub texture: Rc<Texture2d>,
+}
+
+impl Appearance {
+    pub fn load(
+        display: &impl Facade,
+        assets: &mut AssetManager,
+        mesh: &Path,
+        texture: &Path,
+    ) -> Result<Self, String> {
+        Ok(Self {
+            mesh: assets.get_mesh(display, mesh)?,
+            texture: assets.get_texture(display, texture),
+        })
+    }
+}
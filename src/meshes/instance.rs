@@ -0,0 +1,30 @@
+use glium::implement_vertex;
+use nalgebra::Matrix4;
+
+#[derive(Debug, Copy, Clone, Default)]
+pub struct InstanceModel {
+    pub model: [[f32; 4]; 4],
+}
+
+implement_vertex!(InstanceModel, model);
+
+impl InstanceModel {
+    pub fn new(model: Matrix4<f32>) -> Self {
+        InstanceModel {
+            model: model.data.0,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, Default)]
+pub struct InstanceTint {
+    pub tint: [f32; 3],
+}
+
+implement_vertex!(InstanceTint, tint);
+
+impl InstanceTint {
+    pub fn new(tint: [f32; 3]) -> Self {
+        InstanceTint { tint }
+    }
+}
@@ -1,5 +1,5 @@
-use glium::glutin::surface::WindowSurface;
-use glium::{Display, IndexBuffer, VertexBuffer};
+use glium::backend::Facade;
+use glium::{IndexBuffer, VertexBuffer};
 
 use crate::meshes::vertex::Vertex;
 
@@ -10,7 +10,7 @@ pub struct Mesh {
 }
 
 impl Mesh {
-    pub fn new(vertices: Vec<Vertex>, indices: Vec<u32>, display: &Display<WindowSurface>) -> Self {
+    pub fn new(vertices: Vec<Vertex>, indices: Vec<u32>, display: &impl Facade) -> Self {
         let vertex_buffer = VertexBuffer::new(display, &vertices).unwrap();
         let index_buffer = IndexBuffer::new(
             display,
@@ -1,71 +1,78 @@
-use glium::glutin::surface::WindowSurface;
-use glium::{uniform, Display, DrawParameters, Frame, Program, Surface, Texture2d};
-use nalgebra::Matrix4;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
 
+use glium::backend::{Context, Facade};
+use glium::draw_parameters::BackfaceCullingMode;
+use glium::{uniform, DrawParameters, Program, Surface, Texture2d, VertexBuffer};
+use nalgebra::{Matrix4, Vector3};
+
+use crate::assets::asset_manager::AssetManager;
+use crate::assets::shader_error::ShaderError;
+use crate::meshes::appearance::Appearance;
+use crate::meshes::instance::{InstanceModel, InstanceTint};
 use crate::meshes::mesh::Mesh;
+use crate::renderer::frame_context::FrameContext;
+use crate::renderer::render_pass::RenderPass;
+use crate::renderer::render_target::RenderTarget;
+
+const VERTEX_SHADER: &str = "shaders/mesh_vertex.glsl";
+const FRAGMENT_SHADER: &str = "shaders/mesh_fragment.glsl";
+const INSTANCED_VERTEX_SHADER: &str = "shaders/mesh_instanced_vertex.glsl";
+const TINTED_INSTANCED_VERTEX_SHADER: &str = "shaders/mesh_tinted_instanced_vertex.glsl";
+const TINTED_FRAGMENT_SHADER: &str = "shaders/mesh_tinted_fragment.glsl";
 
 pub struct MeshDrawer {
     program: Program,
+    instanced_program: Program,
+    tinted_instanced_program: Program,
     drawing_parameters: DrawParameters<'static>,
 }
 
 impl MeshDrawer {
-    pub fn new(display: &Display<WindowSurface>) -> Self {
-        let vertex_shader_src = r#"
-            #version 140
-    
-            in vec3 position;
-            in vec2 tex_coords;
-            out vec2 v_tex_coords;
-            
-            uniform mat4 perspective;
-            uniform mat4 model;
-            uniform mat4 view;
-    
-            void main() {
-                v_tex_coords = tex_coords;
-                gl_Position = perspective * view * model * vec4(position, 1.0);
-            }
-        "#;
-
-        let fragment_shader_src = r#"
-            #version 140
-    
-            in vec2 v_tex_coords;
-            out vec4 color;
-            
-            uniform sampler2D tex;
-            
-            void main() {
-                color = texture(tex, v_tex_coords);
-            }
-        "#;
+    pub fn new(display: &impl Facade, assets: &mut AssetManager) -> Result<Self, ShaderError> {
+        let program = assets.load_program(
+            display,
+            Path::new(VERTEX_SHADER),
+            Path::new(TINTED_FRAGMENT_SHADER),
+        )?;
+
+        let instanced_program = assets.load_program(
+            display,
+            Path::new(INSTANCED_VERTEX_SHADER),
+            Path::new(FRAGMENT_SHADER),
+        )?;
 
-        let program =
-            Program::from_source(display, vertex_shader_src, fragment_shader_src, None).unwrap();
+        let tinted_instanced_program = assets.load_program(
+            display,
+            Path::new(TINTED_INSTANCED_VERTEX_SHADER),
+            Path::new(TINTED_FRAGMENT_SHADER),
+        )?;
 
-        let mut drawing_parameters = DrawParameters::default();
-        drawing_parameters.depth = glium::Depth {
-            test: glium::draw_parameters::DepthTest::IfLess,
-            write: true,
+        let drawing_parameters = DrawParameters {
+            depth: glium::Depth {
+                test: glium::draw_parameters::DepthTest::IfLess,
+                write: true,
+                ..Default::default()
+            },
+            backface_culling: glium::draw_parameters::BackfaceCullingMode::CullClockwise,
             ..Default::default()
         };
-        drawing_parameters.backface_culling =
-            glium::draw_parameters::BackfaceCullingMode::CullClockwise;
 
-        Self {
+        Ok(Self {
             program,
+            instanced_program,
+            tinted_instanced_program,
             drawing_parameters,
-        }
+        })
     }
 
     pub fn draw(
         &self,
-        target: &mut Frame,
+        target: &mut impl Surface,
+        frame: &FrameContext,
         mesh: &Mesh,
-        perspective: &Matrix4<f32>,
-        view: &Matrix4<f32>,
         model: &Matrix4<f32>,
+        tint: [f32; 3],
         texture: &Texture2d,
     ) {
         target
@@ -74,13 +81,207 @@ impl MeshDrawer {
                 &mesh.index_buffer,
                 &self.program,
                 &uniform! {
-                    perspective: perspective.data.0,
+                    perspective: frame.perspective.data.0,
                     model: model.data.0,
-                    view: view.data.0,
+                    view: frame.view.data.0,
+                    tint: tint,
+                    clip_plane: get_clip_plane(frame),
                     tex: texture,
                 },
-                &self.drawing_parameters,
+                &self.get_drawing_parameters(frame),
             )
             .unwrap();
     }
+
+    /// Mirrored frames turn the triangles around and clip everything below the mirror.
+    fn get_drawing_parameters(&self, frame: &FrameContext) -> DrawParameters<'static> {
+        match frame.mirror_height {
+            Some(_) => DrawParameters {
+                backface_culling: BackfaceCullingMode::CullCounterClockwise,
+                clip_planes_bitmask: 1,
+                ..self.drawing_parameters.clone()
+            },
+            None => self.drawing_parameters.clone(),
+        }
+    }
+
+    pub fn draw_instanced(
+        &self,
+        target: &mut impl Surface,
+        frame: &FrameContext,
+        mesh: &Mesh,
+        models: &VertexBuffer<InstanceModel>,
+        tints: Option<&VertexBuffer<InstanceTint>>,
+        texture: &Texture2d,
+    ) {
+        let uniforms = uniform! {
+            perspective: frame.perspective.data.0,
+            view: frame.view.data.0,
+            clip_plane: get_clip_plane(frame),
+            tex: texture,
+        };
+        let drawing_parameters = self.get_drawing_parameters(frame);
+
+        // Models without a matching tint are drawn untinted rather than failing the
+        // draw call on the instance count mismatch.
+        let tinted_count = tints.map_or(0, |tints| tints.len().min(models.len()));
+        if let Some(tints) = tints.filter(|_| tinted_count > 0) {
+            target
+                .draw(
+                    (
+                        &mesh.vertex_buffer,
+                        models
+                            .slice(..tinted_count)
+                            .unwrap()
+                            .per_instance()
+                            .unwrap(),
+                        tints.slice(..tinted_count).unwrap().per_instance().unwrap(),
+                    ),
+                    &mesh.index_buffer,
+                    &self.tinted_instanced_program,
+                    &uniforms,
+                    &drawing_parameters,
+                )
+                .unwrap();
+        }
+
+        if tinted_count < models.len() {
+            target
+                .draw(
+                    (
+                        &mesh.vertex_buffer,
+                        models
+                            .slice(tinted_count..)
+                            .unwrap()
+                            .per_instance()
+                            .unwrap(),
+                    ),
+                    &mesh.index_buffer,
+                    &self.instanced_program,
+                    &uniforms,
+                    &drawing_parameters,
+                )
+                .unwrap();
+        }
+    }
+}
+
+/// Draws the ducks of the pool, grouped by appearance, and, when the benchmark is
+/// enabled, its extra ducks.
+impl RenderPass for MeshDrawer {
+    fn render(&self, context: &Rc<Context>, target: &mut RenderTarget, frame: &FrameContext) {
+        let water_level = frame.water_height * frame.scale;
+        for (index, appearance) in frame.appearances.iter().enumerate() {
+            let models = frame.flock.get_models(water_level, index);
+            let tints = frame.flock.get_tints(index);
+            self.render_models(context, target, frame, appearance, &models, &tints);
+        }
+
+        let benchmark_models =
+            get_benchmark_models(frame.benchmark_count, frame.scale, frame.water_height);
+        self.render_models(
+            context,
+            target,
+            frame,
+            &frame.appearances[0],
+            &benchmark_models,
+            &[],
+        );
+    }
+
+    fn reload(&mut self, context: &Rc<Context>, assets: &mut AssetManager, changed: &[PathBuf]) {
+        assets.reload_program(
+            context,
+            &mut self.program,
+            Path::new(VERTEX_SHADER),
+            Path::new(TINTED_FRAGMENT_SHADER),
+            changed,
+        );
+        assets.reload_program(
+            context,
+            &mut self.instanced_program,
+            Path::new(INSTANCED_VERTEX_SHADER),
+            Path::new(FRAGMENT_SHADER),
+            changed,
+        );
+        assets.reload_program(
+            context,
+            &mut self.tinted_instanced_program,
+            Path::new(TINTED_INSTANCED_VERTEX_SHADER),
+            Path::new(TINTED_FRAGMENT_SHADER),
+            changed,
+        );
+    }
+
+    fn is_reflected(&self) -> bool {
+        true
+    }
+}
+
+impl MeshDrawer {
+    /// Draws the models instanced or one at a time, as the frame is set to. Like the
+    /// instanced path, models past the last tint are drawn untinted.
+    fn render_models(
+        &self,
+        context: &Rc<Context>,
+        target: &mut impl Surface,
+        frame: &FrameContext,
+        appearance: &Appearance,
+        models: &[InstanceModel],
+        tints: &[InstanceTint],
+    ) {
+        if models.is_empty() {
+            return;
+        }
+
+        if frame.instanced_rendering {
+            let models = VertexBuffer::new(context, models).unwrap();
+            let tints = (!tints.is_empty()).then(|| VertexBuffer::new(context, tints).unwrap());
+            self.draw_instanced(
+                target,
+                frame,
+                &appearance.mesh,
+                &models,
+                tints.as_ref(),
+                &appearance.texture,
+            );
+        } else {
+            for (i, model) in models.iter().enumerate() {
+                let tint = tints.get(i).map_or([1.0, 1.0, 1.0], |tint| tint.tint);
+                self.draw(
+                    target,
+                    frame,
+                    &appearance.mesh,
+                    &Matrix4::from(model.model),
+                    tint,
+                    &appearance.texture,
+                );
+            }
+        }
+    }
+}
+
+/// The world space plane of the mirror as `ax + by + cz + d`, positive above it.
+fn get_clip_plane(frame: &FrameContext) -> [f32; 4] {
+    match frame.mirror_height {
+        Some(height) => [0.0, 1.0, 0.0, -height],
+        None => [0.0; 4],
+    }
+}
+
+/// A grid of `count` small ducks floating over the whole pool.
+fn get_benchmark_models(count: usize, pool_scale: f32, water_height: f32) -> Vec<InstanceModel> {
+    let side = (count as f32).sqrt().ceil() as usize;
+    let extent = 0.9 * pool_scale;
+    let spacing = 2.0 * extent / side as f32;
+    (0..count)
+        .map(|i| {
+            let x = -extent + spacing * ((i % side) as f32 + 0.5);
+            let z = -extent + spacing * ((i / side) as f32 + 0.5);
+            InstanceModel::new(
+                Matrix4::new_translation(&Vector3::new(x, water_height * pool_scale, z))
+                    * Matrix4::new_scaling(0.002),
+            )
+        })
+        .collect()
 }
@@ -1,4 +1,6 @@
+pub mod appearance;
+pub mod instance;
 pub mod mesh;
 pub mod mesh_drawer;
-pub mod read_mesh;
-mod vertex;
+pub mod parse_mesh;
+pub mod vertex;
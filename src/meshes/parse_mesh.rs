@@ -0,0 +1,111 @@
+use std::str::FromStr;
+
+use glium::backend::Facade;
+
+use crate::meshes::mesh::Mesh;
+use crate::meshes::vertex::Vertex;
+
+/// Parses a mesh given as a vertex count, one `x y z nx ny nz u v` line per vertex, a
+/// triangle count and one line of three indices per triangle.
+pub fn parse_mesh(content: &str, display: &impl Facade) -> Result<Mesh, String> {
+    let (vertices, indices) = parse_mesh_data(content)?;
+    Ok(Mesh::new(vertices, indices, display))
+}
+
+/// The vertices and indices of a mesh in the format of `parse_mesh`. The error names
+/// the first line that does not fit.
+pub fn parse_mesh_data(content: &str) -> Result<(Vec<Vertex>, Vec<u32>), String> {
+    let mut lines = content.lines().enumerate();
+    let mut next_line = || {
+        lines
+            .next()
+            .map(|(index, line)| (index + 1, line))
+            .ok_or_else(|| "unexpected end of file".to_string())
+    };
+
+    let [vertices_count] = parse_values::<usize, 1>(next_line()?)?;
+    let mut vertices = Vec::with_capacity(vertices_count);
+    for _ in 0..vertices_count {
+        let [x, y, z, nx, ny, nz, u, v] = parse_values::<f32, 8>(next_line()?)?;
+        vertices.push(Vertex::new([x, y, z], [nx, ny, nz], [u, v]));
+    }
+
+    let [triangles_count] = parse_values::<usize, 1>(next_line()?)?;
+    let mut indices = Vec::with_capacity(triangles_count * 3);
+    for _ in 0..triangles_count {
+        let (line_number, line) = next_line()?;
+        let triangle = parse_values::<u32, 3>((line_number, line))?;
+        if let Some(index) = triangle
+            .iter()
+            .find(|&&index| index as usize >= vertices_count)
+        {
+            return Err(format!(
+                "line {}: index {} is out of range for {} vertices",
+                line_number, index, vertices_count
+            ));
+        }
+        indices.extend(triangle);
+    }
+    Ok((vertices, indices))
+}
+
+fn parse_values<T: FromStr, const N: usize>(
+    (line_number, line): (usize, &str),
+) -> Result<[T; N], String> {
+    let values = line
+        .split_whitespace()
+        .map(|value| {
+            value
+                .parse::<T>()
+                .map_err(|_| format!("line {}: invalid number {}", line_number, value))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let count = values.len();
+    values.try_into().map_err(|_| {
+        format!(
+            "line {}: expected {} values, found {}",
+            line_number, N, count
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TRIANGLE: &str = "3
+0 0 0 0 1 0 0 0
+1 0 0 0 1 0 1 0
+0 0 1 0 1 0 0 1
+1
+0 1 2
+";
+
+    #[test]
+    fn parses_a_triangle() {
+        let (vertices, indices) = parse_mesh_data(TRIANGLE).unwrap();
+        assert_eq!(vertices.len(), 3);
+        assert_eq!(indices, [0, 1, 2]);
+    }
+
+    #[test]
+    fn reports_the_failing_line() {
+        assert_eq!(parse_mesh_data("").unwrap_err(), "unexpected end of file");
+        assert_eq!(
+            parse_mesh_data(&TRIANGLE.replace("1 0 1 0\n", "1 0 1\n")).unwrap_err(),
+            "line 3: expected 8 values, found 7"
+        );
+        assert_eq!(
+            parse_mesh_data(&TRIANGLE.replace("1 0 1 0", "1 0 x 0")).unwrap_err(),
+            "line 3: invalid number x"
+        );
+        assert_eq!(
+            parse_mesh_data(&TRIANGLE.replace("0 1 2", "0 1 3")).unwrap_err(),
+            "line 6: index 3 is out of range for 3 vertices"
+        );
+        assert_eq!(
+            parse_mesh_data(&TRIANGLE.replace("\n0 1 2\n", "")).unwrap_err(),
+            "unexpected end of file"
+        );
+    }
+}
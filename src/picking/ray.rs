@@ -0,0 +1,58 @@
+use nalgebra::{Matrix4, Point2, Point3, Vector3};
+
+pub struct Ray {
+    pub origin: Point3<f32>,
+    pub direction: Vector3<f32>,
+}
+
+impl Ray {
+    pub fn from_screen(
+        screen_position: (f64, f64),
+        screen_size: (u32, u32),
+        perspective: &Matrix4<f32>,
+        view: &Matrix4<f32>,
+    ) -> Option<Self> {
+        let x = 2.0 * screen_position.0 as f32 / screen_size.0 as f32 - 1.0;
+        let y = 1.0 - 2.0 * screen_position.1 as f32 / screen_size.1 as f32;
+        let inverse = (perspective * view).try_inverse()?;
+
+        let near = inverse.transform_point(&Point3::new(x, y, -1.0));
+        let far = inverse.transform_point(&Point3::new(x, y, 1.0));
+
+        Some(Self {
+            origin: near,
+            direction: (far - near).normalize(),
+        })
+    }
+
+    pub fn intersect_horizontal_plane(&self, height: f32) -> Option<Point3<f32>> {
+        if self.direction.y.abs() < f32::EPSILON {
+            return None;
+        }
+
+        let t = (height - self.origin.y) / self.direction.y;
+        if t < 0.0 {
+            return None;
+        }
+
+        Some(self.origin + t * self.direction)
+    }
+}
+
+pub fn pick_water(
+    screen_position: (f64, f64),
+    screen_size: (u32, u32),
+    perspective: &Matrix4<f32>,
+    view: &Matrix4<f32>,
+    water_height: f32,
+    pool_scale: f32,
+) -> Option<Point2<f32>> {
+    let ray = Ray::from_screen(screen_position, screen_size, perspective, view)?;
+    let hit = ray.intersect_horizontal_plane(water_height * pool_scale)?;
+
+    if hit.x.abs() > pool_scale || hit.z.abs() > pool_scale {
+        return None;
+    }
+
+    Some(Point2::new(hit.x, hit.z))
+}
@@ -0,0 +1,269 @@
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+use glium::backend::Facade;
+use glium::Texture2d;
+use nalgebra::Point2;
+use rand::rngs::StdRng;
+use rand::{RngCore, SeedableRng};
+
+use crate::assets::asset_manager::AssetManager;
+use crate::capabilities::capabilities::Capabilities;
+use crate::ducks::flock::Flock;
+use crate::meshes::appearance::Appearance;
+use crate::pool::pool_error::PoolError;
+use crate::renderer::frame_context::EnvironmentTextures;
+use crate::scene::scene::Scene;
+use crate::water::water_simulation::WaterSimulation;
+
+/// The most simulated seconds a single update runs. The rest of a long frame is
+/// dropped, so a slow frame leaves the water behind instead of slowing every frame
+/// after it.
+const MAX_TIME_PER_UPDATE: f32 = 0.125;
+/// The solver time step of a 256 cell grid, which the duck speeds and the rain rate are
+/// given in. Finer grids take shorter steps and move the ducks less in each.
+const REFERENCE_DT: f32 = 1.0 / 256.0;
+
+/// The simulated pool: the water, the ducks swimming on it and the meshes and textures
+/// they are drawn with.
+pub struct Pool {
+    appearances: Vec<Appearance>,
+    vulkan_texture: Rc<Texture2d>,
+    sky_texture: Rc<Texture2d>,
+    sand_texture: Rc<Texture2d>,
+    water_simulation: WaterSimulation,
+    rng: StdRng,
+    seed: u64,
+    scene: Scene,
+    capabilities: Capabilities,
+    time: f32,
+    time_to_compute: f32,
+    rain_steps: f32,
+    pub scale: f32,
+    pub flock: Flock,
+    pub duck_count: usize,
+    pub water_height: f32,
+    pub rain: bool,
+    pub water_disturbances: Vec<(Point2<f32>, f32)>,
+    pub duck_destinations: Vec<(usize, Point2<f32>)>,
+}
+
+impl Pool {
+    /// Creates the pool described by `scene`, with its meshes and textures taken from
+    /// `assets` and all randomness (rain and duck paths) drawn from a single generator
+    /// seeded with `seed`, so runs with the same seed are identical.
+    pub fn new(
+        display: &impl Facade,
+        scene: &Scene,
+        assets: &mut AssetManager,
+        capabilities: &Capabilities,
+        seed: u64,
+    ) -> Result<Self, PoolError> {
+        let water_path = capabilities
+            .get_water_path()
+            .ok_or(PoolError::NoWaterPath)?;
+        let mut rng = StdRng::seed_from_u64(seed);
+        let flock = Flock::new(
+            &scene.objects,
+            scene.ducks.count,
+            scene.pool.scale,
+            &mut rng,
+        );
+        let appearances = get_appearance_paths(scene)
+            .map(|(mesh, texture)| Appearance::load(display, assets, mesh, texture))
+            .collect::<Result<_, _>>()
+            .map_err(PoolError::Mesh)?;
+
+        Ok(Self {
+            appearances,
+            vulkan_texture: assets.get_texture(display, &scene.environment.walls),
+            sky_texture: assets.get_texture(display, &scene.environment.sky),
+            sand_texture: assets.get_texture(display, &scene.environment.sand),
+            water_simulation: WaterSimulation::new(
+                display,
+                assets,
+                water_path,
+                capabilities.get_grid_size(scene.water.grid_size),
+                scene.water.wave_speed,
+            )?,
+            rng,
+            seed,
+            scene: scene.clone(),
+            capabilities: capabilities.clone(),
+            time: 0.0,
+            time_to_compute: 0.0,
+            rain_steps: 0.0,
+            scale: scene.pool.scale,
+            flock,
+            duck_count: scene.get_duck_count(),
+            water_height: scene.water.height,
+            rain: scene.water.rain,
+            water_disturbances: vec![],
+            duck_destinations: vec![],
+        })
+    }
+
+    /// Creates a fresh pool with the same seed and settings, so that a session can be
+    /// recorded from a state that a replay can reproduce.
+    pub fn restart(
+        &self,
+        display: &impl Facade,
+        assets: &mut AssetManager,
+    ) -> Result<Self, PoolError> {
+        Ok(Self {
+            duck_count: self.duck_count,
+            water_height: self.water_height,
+            rain: self.rain,
+            ..Self::new(display, &self.scene, assets, &self.capabilities, self.seed)?
+        })
+    }
+
+    /// Reloads the meshes and textures and rebuilds the water shaders among the changed
+    /// asset keys. A mesh that fails to load keeps its previous version.
+    pub fn reload(
+        &mut self,
+        display: &impl Facade,
+        assets: &mut AssetManager,
+        changed: &[PathBuf],
+    ) {
+        for (appearance, (mesh, texture)) in self
+            .appearances
+            .iter_mut()
+            .zip(get_appearance_paths(&self.scene))
+        {
+            match assets.get_mesh(display, mesh) {
+                Ok(mesh) => appearance.mesh = mesh,
+                Err(error) => eprintln!("{}; keeping the previous mesh", error),
+            }
+            appearance.texture = assets.get_texture(display, texture);
+        }
+        self.vulkan_texture = assets.get_texture(display, &self.scene.environment.walls);
+        self.sky_texture = assets.get_texture(display, &self.scene.environment.sky);
+        self.sand_texture = assets.get_texture(display, &self.scene.environment.sand);
+
+        self.water_simulation.reload(display, assets, changed);
+    }
+
+    pub fn get_seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Advances the simulation by `dt` seconds in as many steps of the water solver's
+    /// time step as fit, running at most `MAX_TIME_PER_UPDATE` seconds of them.
+    pub fn update(&mut self, dt: f32) {
+        self.flock.resize(self.duck_count, &mut self.rng);
+        for (index, destination) in self.duck_destinations.drain(..) {
+            self.flock.steer(index, destination);
+        }
+
+        self.time += dt;
+        self.time_to_compute += dt;
+        let max_steps = (MAX_TIME_PER_UPDATE / self.water_simulation.get_dt()) as u32;
+        let mut steps = 0;
+        while self.time_to_compute >= self.water_simulation.get_dt() {
+            if steps == max_steps {
+                self.time_to_compute = 0.0;
+                break;
+            }
+
+            self.step();
+            steps += 1;
+            self.time_to_compute -= self.water_simulation.get_dt();
+        }
+    }
+
+    fn step(&mut self) {
+        let reference_steps = self.water_simulation.get_dt() / REFERENCE_DT;
+        if self.rain {
+            // Rain is rolled once per reference step, so it falls at the same rate on
+            // every grid size.
+            self.rain_steps += reference_steps;
+            let size = self.water_simulation.get_size();
+            while self.rain_steps >= 1.0 {
+                self.rain_steps -= 1.0;
+                if let Some((x, y)) = get_rain_drop(&mut self.rng, size) {
+                    self.water_simulation.bend(x, y);
+                }
+            }
+        }
+
+        for (position, strength) in self.water_disturbances.drain(..) {
+            self.water_simulation
+                .bend_at(position, self.scale, strength);
+        }
+
+        self.flock.update(reference_steps, &mut self.rng);
+        self.flock.bend_water(&self.water_simulation, self.scale);
+
+        self.water_simulation.compute();
+    }
+
+    /// The number of water simulation cells along each side of the pool.
+    pub fn get_grid_size(&self) -> u32 {
+        self.water_simulation.get_size()
+    }
+
+    pub fn get_time(&self) -> f32 {
+        self.time
+    }
+
+    /// The meshes and textures the ducks are drawn with: the random ducks' first, then
+    /// one for each object of the scene.
+    pub fn get_appearances(&self) -> &[Appearance] {
+        &self.appearances
+    }
+
+    pub fn get_environment(&self) -> EnvironmentTextures<'_> {
+        EnvironmentTextures {
+            walls: &self.vulkan_texture,
+            sky: &self.sky_texture,
+            sand: &self.sand_texture,
+        }
+    }
+
+    pub fn get_normal_texture(&self) -> &Texture2d {
+        self.water_simulation.get_normal_texture()
+    }
+}
+
+/// The mesh and texture paths of each appearance, in the order of `get_appearances`.
+fn get_appearance_paths(scene: &Scene) -> impl Iterator<Item = (&Path, &Path)> {
+    let ducks = std::iter::once((&scene.ducks.mesh, &scene.ducks.texture));
+    let objects = scene
+        .objects
+        .iter()
+        .map(|object| (&object.mesh, &object.texture));
+    ducks
+        .chain(objects)
+        .map(|(mesh, texture)| (mesh.as_path(), texture.as_path()))
+}
+
+/// The cell of a `size` grid a rain drop falls on this step, if any. Cells are picked
+/// from a grid twelve times as large, so most steps have no drop.
+pub fn get_rain_drop(rng: &mut StdRng, size: u32) -> Option<(i32, i32)> {
+    let x = rng.next_u32() % size * 12;
+    let y = rng.next_u32() % size * 12;
+    (x < size && y < size).then_some((x as i32, y as i32))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_rain_drops(seed: u64, steps: usize) -> Vec<Option<(i32, i32)>> {
+        let mut rng = StdRng::seed_from_u64(seed);
+        (0..steps).map(|_| get_rain_drop(&mut rng, 256)).collect()
+    }
+
+    #[test]
+    fn same_seed_gives_same_rain_drops() {
+        let drops = get_rain_drops(7, 2000);
+        assert!(drops.iter().any(Option::is_some));
+        assert_eq!(drops, get_rain_drops(7, 2000));
+    }
+
+    #[test]
+    fn different_seeds_give_different_rain_drops() {
+        assert_ne!(get_rain_drops(7, 2000), get_rain_drops(8, 2000));
+    }
+}
@@ -0,0 +1,43 @@
+use std::error::Error;
+use std::fmt;
+
+use crate::assets::shader_error::ShaderError;
+
+/// Why a pool could not be created.
+#[derive(Debug, Clone)]
+pub enum PoolError {
+    /// The context supports neither way of simulating the water.
+    NoWaterPath,
+    /// A mesh of the scene could not be loaded.
+    Mesh(String),
+    /// A water shader could not be built.
+    Shader(ShaderError),
+}
+
+impl fmt::Display for PoolError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NoWaterPath => f.write_str(
+                "the water simulation needs compute shaders or float render targets, \
+                 and this context supports neither",
+            ),
+            Self::Mesh(error) => f.write_str(error),
+            Self::Shader(error) => error.fmt(f),
+        }
+    }
+}
+
+impl Error for PoolError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Shader(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<ShaderError> for PoolError {
+    fn from(error: ShaderError) -> Self {
+        Self::Shader(error)
+    }
+}
@@ -0,0 +1,80 @@
+use glium::Texture2d;
+use nalgebra::{Matrix4, Point3, Vector3};
+
+use crate::camera::cameras::Cameras;
+use crate::ducks::flock::Flock;
+use crate::meshes::appearance::Appearance;
+use crate::pool::pool::Pool;
+
+/// The textures of the pool walls, the sky and the sand, which also make up the
+/// environment the water reflects and refracts.
+#[derive(Clone, Copy)]
+pub struct EnvironmentTextures<'a> {
+    pub walls: &'a Texture2d,
+    pub sky: &'a Texture2d,
+    pub sand: &'a Texture2d,
+}
+
+/// What the render passes share while drawing one frame.
+#[derive(Clone, Copy)]
+pub struct FrameContext<'a> {
+    pub perspective: Matrix4<f32>,
+    pub view: Matrix4<f32>,
+    pub camera_position: Point3<f32>,
+    /// Simulated seconds since the pool was created.
+    pub time: f32,
+    pub environment: EnvironmentTextures<'a>,
+    /// The scale of the pool in world units.
+    pub scale: f32,
+    /// The water level as a fraction of the pool scale.
+    pub water_height: f32,
+    pub water_normals: &'a Texture2d,
+    /// The meshes and textures of the ducks, indexed like the flock's appearances.
+    pub appearances: &'a [Appearance],
+    pub flock: &'a Flock,
+    /// Whether the ducks are drawn instanced, set by the renderer.
+    pub instanced_rendering: bool,
+    /// The number of extra ducks the benchmark draws, set by the renderer.
+    pub benchmark_count: usize,
+    /// The height of the horizontal plane the frame is mirrored about. Passes drawing a
+    /// mirrored frame only draw what is above the plane.
+    pub mirror_height: Option<f32>,
+    /// The ducks mirrored about the water, in screen space, when reflections are enabled.
+    pub reflection: Option<&'a Texture2d>,
+}
+
+impl<'a> FrameContext<'a> {
+    /// Describes the frame of `pool` as seen by the active camera.
+    pub fn new(pool: &'a Pool, cameras: &Cameras) -> Self {
+        Self {
+            perspective: cameras.get_perspective(),
+            view: cameras.get_view(),
+            camera_position: cameras.get_position(),
+            time: pool.get_time(),
+            environment: pool.get_environment(),
+            scale: pool.scale,
+            water_height: pool.water_height,
+            water_normals: pool.get_normal_texture(),
+            appearances: pool.get_appearances(),
+            flock: &pool.flock,
+            instanced_rendering: true,
+            benchmark_count: 0,
+            mirror_height: None,
+            reflection: None,
+        }
+    }
+
+    /// The same frame seen in a mirror lying on the horizontal plane at `height`.
+    pub fn get_mirrored(&self, height: f32) -> Self {
+        let mirror = Matrix4::new_translation(&Vector3::new(0.0, 2.0 * height, 0.0))
+            * Matrix4::new_nonuniform_scaling(&Vector3::new(1.0, -1.0, 1.0));
+        let mut camera_position = self.camera_position;
+        camera_position.y = 2.0 * height - camera_position.y;
+        Self {
+            view: self.view * mirror,
+            camera_position,
+            mirror_height: Some(height),
+            ..*self
+        }
+    }
+}
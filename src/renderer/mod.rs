@@ -0,0 +1,5 @@
+pub mod frame_context;
+pub mod render_pass;
+pub mod render_target;
+#[allow(clippy::module_inception)]
+pub mod renderer;
@@ -0,0 +1,22 @@
+use std::path::PathBuf;
+use std::rc::Rc;
+
+use glium::backend::Context;
+
+use crate::assets::asset_manager::AssetManager;
+use crate::renderer::frame_context::FrameContext;
+use crate::renderer::render_target::RenderTarget;
+
+/// One step of drawing a frame, such as the ducks, the pool walls or the water. Everything
+/// a pass needs beyond its own programs and geometry comes from the frame context.
+pub trait RenderPass {
+    fn render(&self, context: &Rc<Context>, target: &mut RenderTarget, frame: &FrameContext);
+
+    /// Rebuilds the programs whose shader files changed.
+    fn reload(&mut self, context: &Rc<Context>, assets: &mut AssetManager, changed: &[PathBuf]);
+
+    /// Whether the pass is also drawn mirrored about the water, into the reflection.
+    fn is_reflected(&self) -> bool {
+        false
+    }
+}
@@ -0,0 +1,160 @@
+use glium::framebuffer::{MultiOutputFrameBuffer, SimpleFrameBuffer};
+use glium::index::IndicesSource;
+use glium::uniforms::{MagnifySamplerFilter, Uniforms};
+use glium::vertex::MultiVerticesSource;
+use glium::{BlitMask, BlitTarget, DrawError, DrawParameters, Frame, Program, Rect, Surface};
+
+/// The surface a frame is drawn to: the window or an offscreen framebuffer. `Surface`
+/// has generic methods and cannot be a trait object, so render passes take this
+/// instead and draw to it like to any other surface.
+pub enum RenderTarget<'a, 'b> {
+    Frame(&'a mut Frame),
+    Framebuffer(&'a mut SimpleFrameBuffer<'b>),
+}
+
+impl Surface for RenderTarget<'_, '_> {
+    fn clear(
+        &mut self,
+        rect: Option<&Rect>,
+        color: Option<(f32, f32, f32, f32)>,
+        color_srgb: bool,
+        depth: Option<f32>,
+        stencil: Option<i32>,
+    ) {
+        match self {
+            Self::Frame(frame) => frame.clear(rect, color, color_srgb, depth, stencil),
+            Self::Framebuffer(framebuffer) => {
+                framebuffer.clear(rect, color, color_srgb, depth, stencil)
+            }
+        }
+    }
+
+    fn get_dimensions(&self) -> (u32, u32) {
+        match self {
+            Self::Frame(frame) => frame.get_dimensions(),
+            Self::Framebuffer(framebuffer) => framebuffer.get_dimensions(),
+        }
+    }
+
+    fn get_depth_buffer_bits(&self) -> Option<u16> {
+        match self {
+            Self::Frame(frame) => frame.get_depth_buffer_bits(),
+            Self::Framebuffer(framebuffer) => framebuffer.get_depth_buffer_bits(),
+        }
+    }
+
+    fn get_stencil_buffer_bits(&self) -> Option<u16> {
+        match self {
+            Self::Frame(frame) => frame.get_stencil_buffer_bits(),
+            Self::Framebuffer(framebuffer) => framebuffer.get_stencil_buffer_bits(),
+        }
+    }
+
+    fn draw<'a, 'b, V, I, U>(
+        &mut self,
+        vertices: V,
+        indices: I,
+        program: &Program,
+        uniforms: &U,
+        draw_parameters: &DrawParameters<'_>,
+    ) -> Result<(), DrawError>
+    where
+        V: MultiVerticesSource<'b>,
+        I: Into<IndicesSource<'a>>,
+        U: Uniforms,
+    {
+        match self {
+            Self::Frame(frame) => frame.draw(vertices, indices, program, uniforms, draw_parameters),
+            Self::Framebuffer(framebuffer) => {
+                framebuffer.draw(vertices, indices, program, uniforms, draw_parameters)
+            }
+        }
+    }
+
+    fn blit_buffers_from_frame(
+        &self,
+        source_rect: &Rect,
+        target_rect: &BlitTarget,
+        filter: MagnifySamplerFilter,
+        mask: BlitMask,
+    ) {
+        match self {
+            Self::Frame(frame) => {
+                frame.blit_buffers_from_frame(source_rect, target_rect, filter, mask)
+            }
+            Self::Framebuffer(framebuffer) => {
+                framebuffer.blit_buffers_from_frame(source_rect, target_rect, filter, mask)
+            }
+        }
+    }
+
+    fn blit_buffers_from_simple_framebuffer(
+        &self,
+        source: &SimpleFrameBuffer<'_>,
+        source_rect: &Rect,
+        target_rect: &BlitTarget,
+        filter: MagnifySamplerFilter,
+        mask: BlitMask,
+    ) {
+        match self {
+            Self::Frame(frame) => frame.blit_buffers_from_simple_framebuffer(
+                source,
+                source_rect,
+                target_rect,
+                filter,
+                mask,
+            ),
+            Self::Framebuffer(framebuffer) => framebuffer.blit_buffers_from_simple_framebuffer(
+                source,
+                source_rect,
+                target_rect,
+                filter,
+                mask,
+            ),
+        }
+    }
+
+    fn blit_buffers_from_multioutput_framebuffer(
+        &self,
+        source: &MultiOutputFrameBuffer<'_>,
+        source_rect: &Rect,
+        target_rect: &BlitTarget,
+        filter: MagnifySamplerFilter,
+        mask: BlitMask,
+    ) {
+        match self {
+            Self::Frame(frame) => frame.blit_buffers_from_multioutput_framebuffer(
+                source,
+                source_rect,
+                target_rect,
+                filter,
+                mask,
+            ),
+            Self::Framebuffer(framebuffer) => framebuffer
+                .blit_buffers_from_multioutput_framebuffer(
+                    source,
+                    source_rect,
+                    target_rect,
+                    filter,
+                    mask,
+                ),
+        }
+    }
+
+    fn blit_color<S>(
+        &self,
+        source_rect: &Rect,
+        target: &S,
+        target_rect: &BlitTarget,
+        filter: MagnifySamplerFilter,
+    ) where
+        S: Surface,
+    {
+        match self {
+            Self::Frame(frame) => frame.blit_color(source_rect, target, target_rect, filter),
+            Self::Framebuffer(framebuffer) => {
+                framebuffer.blit_color(source_rect, target, target_rect, filter)
+            }
+        }
+    }
+}
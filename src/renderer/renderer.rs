@@ -0,0 +1,119 @@
+use std::cell::RefCell;
+use std::path::PathBuf;
+
+use glium::backend::Facade;
+use glium::Surface;
+
+use crate::assets::asset_manager::AssetManager;
+use crate::assets::shader_error::ShaderError;
+use crate::capture::offscreen_target::OffscreenTarget;
+use crate::cubes::cube_drawer::CubeDrawer;
+use crate::meshes::mesh_drawer::MeshDrawer;
+use crate::renderer::frame_context::FrameContext;
+use crate::renderer::render_pass::RenderPass;
+use crate::renderer::render_target::RenderTarget;
+use crate::water::water_drawer::WaterDrawer;
+
+/// Draws frames of the pool by running its render passes in order, by default the
+/// ducks, the pool walls and then the water. With reflections enabled the reflected
+/// passes are first drawn mirrored about the water into a texture the water pass
+/// samples.
+pub struct Renderer {
+    pub passes: Vec<Box<dyn RenderPass>>,
+    reflection_target: RefCell<Option<OffscreenTarget>>,
+    pub reflection_enabled: bool,
+    pub instanced_rendering: bool,
+    pub benchmark_enabled: bool,
+    pub benchmark_count: usize,
+}
+
+impl Renderer {
+    pub fn new(display: &impl Facade, assets: &mut AssetManager) -> Result<Self, ShaderError> {
+        Ok(Self {
+            passes: vec![
+                Box::new(MeshDrawer::new(display, assets)?),
+                Box::new(CubeDrawer::new(display, assets)?),
+                Box::new(WaterDrawer::new(display, assets)?),
+            ],
+            reflection_target: RefCell::new(None),
+            reflection_enabled: true,
+            instanced_rendering: true,
+            benchmark_enabled: false,
+            benchmark_count: 1000,
+        })
+    }
+
+    /// Rebuilds the programs whose shader files changed.
+    pub fn reload(
+        &mut self,
+        display: &impl Facade,
+        assets: &mut AssetManager,
+        changed: &[PathBuf],
+    ) {
+        for pass in &mut self.passes {
+            pass.reload(display.get_context(), assets, changed);
+        }
+    }
+
+    pub fn render(&self, display: &impl Facade, target: &mut RenderTarget, frame: &FrameContext) {
+        let frame = FrameContext {
+            instanced_rendering: self.instanced_rendering,
+            benchmark_count: if self.benchmark_enabled {
+                self.benchmark_count
+            } else {
+                0
+            },
+            ..*frame
+        };
+        if !self.reflection_enabled {
+            self.render_passes(display, target, &frame, false);
+            return;
+        }
+
+        let (width, height) = target.get_dimensions();
+        let mut reflection_target = self.reflection_target.borrow_mut();
+        if reflection_target
+            .as_ref()
+            .map(OffscreenTarget::get_dimensions)
+            != Some((width, height))
+        {
+            *reflection_target = OffscreenTarget::new(display, width, height).ok();
+        }
+        // A target the size of the frame that cannot be allocated leaves the frame
+        // without reflections.
+        let Some(reflection_target) = reflection_target.as_ref() else {
+            self.render_passes(display, target, &frame, false);
+            return;
+        };
+
+        let mut framebuffer = reflection_target.get_framebuffer(display);
+        framebuffer.clear_color_and_depth((0.0, 0.0, 0.0, 0.0), 1.0);
+        self.render_passes(
+            display,
+            &mut RenderTarget::Framebuffer(&mut framebuffer),
+            &frame.get_mirrored(frame.water_height * frame.scale),
+            true,
+        );
+
+        let frame = FrameContext {
+            reflection: Some(reflection_target.get_color_texture()),
+            ..frame
+        };
+        self.render_passes(display, target, &frame, false);
+    }
+
+    /// Runs the passes in order, or only the reflected ones when drawing the reflection.
+    fn render_passes(
+        &self,
+        display: &impl Facade,
+        target: &mut RenderTarget,
+        frame: &FrameContext,
+        reflection: bool,
+    ) {
+        for pass in &self.passes {
+            if !reflection || pass.is_reflected() {
+                pass.render(display.get_context(), target, frame);
+            }
+        }
+    }
+}
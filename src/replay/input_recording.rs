@@ -0,0 +1,335 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::str::SplitWhitespace;
+
+use chrono::Local;
+use nalgebra::{Point2, Point3, Quaternion, UnitQuaternion};
+
+use crate::camera::bookmarks::Bookmark;
+use crate::camera::cameras::Cameras;
+use crate::pool::pool::Pool;
+use crate::renderer::renderer::Renderer;
+use crate::scene::read_scene::read_scene;
+use crate::scene::scene::Scene;
+
+pub struct DuckInput {
+    pub speed: f32,
+    pub scale: f32,
+    pub tint: [f32; 3],
+}
+
+/// Everything that drove one frame: the time step, the panel settings, the pending
+/// mouse interactions and the camera pose the frame was drawn from.
+pub struct FrameInput {
+    pub dt: f32,
+    pub water_height: f32,
+    pub rain: bool,
+    pub reflection_enabled: bool,
+    pub duck_count: usize,
+    pub ducks: Vec<DuckInput>,
+    pub disturbances: Vec<(Point2<f32>, f32)>,
+    pub destinations: Vec<(usize, Point2<f32>)>,
+    pub camera: Bookmark,
+}
+
+impl FrameInput {
+    /// Captures the inputs of the frame. Must be called right before the pool is updated.
+    pub fn capture(dt: f32, pool: &Pool, renderer: &Renderer, camera: Bookmark) -> Self {
+        Self {
+            dt,
+            water_height: pool.water_height,
+            rain: pool.rain,
+            reflection_enabled: renderer.reflection_enabled,
+            duck_count: pool.duck_count,
+            ducks: pool
+                .flock
+                .ducks
+                .iter()
+                .map(|duck| DuckInput {
+                    speed: duck.speed,
+                    scale: duck.scale,
+                    tint: duck.tint,
+                })
+                .collect(),
+            disturbances: pool.water_disturbances.clone(),
+            destinations: pool.duck_destinations.clone(),
+            camera,
+        }
+    }
+
+    /// Restores the inputs of the frame. The pool must then be updated with `dt`.
+    pub fn apply(&self, pool: &mut Pool, renderer: &mut Renderer, cameras: &mut Cameras) {
+        pool.water_height = self.water_height;
+        pool.rain = self.rain;
+        renderer.reflection_enabled = self.reflection_enabled;
+        pool.duck_count = self.duck_count;
+        for (duck, input) in pool.flock.ducks.iter_mut().zip(&self.ducks) {
+            duck.speed = input.speed;
+            duck.scale = input.scale;
+            duck.tint = input.tint;
+        }
+        pool.water_disturbances = self.disturbances.clone();
+        pool.duck_destinations = self.destinations.clone();
+        cameras.show_pose(&self.camera);
+    }
+}
+
+/// The inputs of a session that started from a fresh pool with the given seed, one
+/// entry per frame, along with the window size, water grid size and scene file the
+/// session ran with, which a replay needs to match.
+pub struct InputRecording {
+    pub seed: u64,
+    pub width: u32,
+    pub height: u32,
+    pub grid_size: u32,
+    pub scene_file: Option<PathBuf>,
+    pub frames: Vec<FrameInput>,
+}
+
+impl InputRecording {
+    pub fn new(
+        seed: u64,
+        width: u32,
+        height: u32,
+        grid_size: u32,
+        scene_file: Option<PathBuf>,
+    ) -> Self {
+        Self {
+            seed,
+            width,
+            height,
+            grid_size,
+            scene_file,
+            frames: vec![],
+        }
+    }
+
+    /// The scene to replay with: `scene` if it was read from the recorded scene file, or
+    /// else the recorded file read again, in either case with the recorded grid size.
+    pub fn get_scene(&self, scene_file: Option<&Path>, scene: &Scene) -> Result<Scene, String> {
+        let mut scene = if self.scene_file.as_deref() == scene_file {
+            scene.clone()
+        } else {
+            println!(
+                "Replaying with the recorded scene {}",
+                self.scene_file
+                    .as_ref()
+                    .map_or(String::from("(default)"), |path| path.display().to_string())
+            );
+            match &self.scene_file {
+                Some(path) => read_scene(path)?,
+                None => Scene::default(),
+            }
+        };
+        scene.water.grid_size = self.grid_size;
+        Ok(scene)
+    }
+}
+
+pub fn get_input_recording_path(directory: &Path) -> io::Result<PathBuf> {
+    fs::create_dir_all(directory)?;
+    let file_name = format!("inputs_{}.txt", Local::now().format("%Y%m%d_%H%M%S"));
+    Ok(directory.join(file_name))
+}
+
+/// Writes a `seed width height grid_size frames` header and a line with the scene file,
+/// empty for the default scene, followed by one line per frame. Every list in a line is
+/// prefixed with its length. Floats are written in their shortest exact form, so
+/// reading the file back gives bit-identical inputs.
+pub fn write_input_recording(path: &Path, recording: &InputRecording) -> io::Result<()> {
+    let scene_file = match &recording.scene_file {
+        Some(scene_file) => scene_file.to_str().ok_or_else(|| {
+            io::Error::new(io::ErrorKind::InvalidInput, "scene path is not valid UTF-8")
+        })?,
+        None => "",
+    };
+    let mut content = format!(
+        "{} {} {} {} {}\n{}\n",
+        recording.seed,
+        recording.width,
+        recording.height,
+        recording.grid_size,
+        recording.frames.len(),
+        scene_file
+    );
+    for frame in &recording.frames {
+        let position = frame.camera.position;
+        let q = frame.camera.orientation.quaternion();
+        content += &format!(
+            "{} {} {} {} {} {} {} {} {} {} {} {}",
+            frame.dt,
+            frame.water_height,
+            frame.rain,
+            frame.reflection_enabled,
+            frame.duck_count,
+            position.x,
+            position.y,
+            position.z,
+            q.i,
+            q.j,
+            q.k,
+            q.w
+        );
+        content += &format!(" {}", frame.ducks.len());
+        for duck in &frame.ducks {
+            let [r, g, b] = duck.tint;
+            content += &format!(" {} {} {} {} {}", duck.speed, duck.scale, r, g, b);
+        }
+        content += &format!(" {}", frame.disturbances.len());
+        for (position, strength) in &frame.disturbances {
+            content += &format!(" {} {} {}", position.x, position.y, strength);
+        }
+        content += &format!(" {}", frame.destinations.len());
+        for (index, destination) in &frame.destinations {
+            content += &format!(" {} {} {}", index, destination.x, destination.y);
+        }
+        content += "\n";
+    }
+    fs::write(path, content)
+}
+
+pub fn read_input_recording(path: &Path) -> io::Result<InputRecording> {
+    let content = fs::read_to_string(path)?;
+    let mut lines = content.lines();
+    let mut header = lines.next().unwrap_or_default().split_whitespace();
+    let mut recording = InputRecording::new(
+        parse_value(&mut header)?,
+        parse_value(&mut header)?,
+        parse_value(&mut header)?,
+        parse_value(&mut header)?,
+        None,
+    );
+    let count = parse_value::<usize>(&mut header)?;
+    let scene_file = lines.next().unwrap_or_default();
+    recording.scene_file = (!scene_file.is_empty()).then(|| PathBuf::from(scene_file));
+    for _ in 0..count {
+        let mut values = lines.next().unwrap_or_default().split_whitespace();
+        recording.frames.push(read_frame(&mut values)?);
+    }
+    Ok(recording)
+}
+
+fn read_frame(values: &mut SplitWhitespace) -> io::Result<FrameInput> {
+    let dt = parse_value(values)?;
+    let water_height = parse_value(values)?;
+    let rain = parse_value(values)?;
+    let reflection_enabled = parse_value(values)?;
+    let duck_count = parse_value(values)?;
+    let position = Point3::new(
+        parse_value(values)?,
+        parse_value(values)?,
+        parse_value(values)?,
+    );
+    let (i, j, k, w) = (
+        parse_value(values)?,
+        parse_value(values)?,
+        parse_value(values)?,
+        parse_value(values)?,
+    );
+    let camera = Bookmark {
+        name: String::new(),
+        position,
+        orientation: UnitQuaternion::from_quaternion(Quaternion::new(w, i, j, k)),
+    };
+
+    let ducks = (0..parse_value::<usize>(values)?)
+        .map(|_| {
+            Ok(DuckInput {
+                speed: parse_value(values)?,
+                scale: parse_value(values)?,
+                tint: [
+                    parse_value(values)?,
+                    parse_value(values)?,
+                    parse_value(values)?,
+                ],
+            })
+        })
+        .collect::<io::Result<_>>()?;
+    let disturbances = (0..parse_value::<usize>(values)?)
+        .map(|_| {
+            let position = Point2::new(parse_value(values)?, parse_value(values)?);
+            Ok((position, parse_value(values)?))
+        })
+        .collect::<io::Result<_>>()?;
+    let destinations = (0..parse_value::<usize>(values)?)
+        .map(|_| {
+            let index = parse_value(values)?;
+            Ok((
+                index,
+                Point2::new(parse_value(values)?, parse_value(values)?),
+            ))
+        })
+        .collect::<io::Result<_>>()?;
+
+    Ok(FrameInput {
+        dt,
+        water_height,
+        rain,
+        reflection_enabled,
+        duck_count,
+        ducks,
+        disturbances,
+        destinations,
+        camera,
+    })
+}
+
+fn parse_value<T: std::str::FromStr>(values: &mut SplitWhitespace) -> io::Result<T> {
+    values
+        .next()
+        .and_then(|value| value.parse::<T>().ok())
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "malformed input recording file"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recording_survives_a_round_trip() {
+        let mut recording =
+            InputRecording::new(7, 320, 240, 128, Some(PathBuf::from("scenes/example.toml")));
+        recording.frames.push(FrameInput {
+            dt: 1.0 / 60.0,
+            water_height: 0.1,
+            rain: false,
+            reflection_enabled: true,
+            duck_count: 2,
+            ducks: vec![DuckInput {
+                speed: 0.01,
+                scale: 0.005,
+                tint: [1.0, 0.5, 0.25],
+            }],
+            disturbances: vec![(Point2::new(0.5, -0.5), 1.0)],
+            destinations: vec![(1, Point2::new(-1.0, 2.0))],
+            camera: Bookmark {
+                name: String::new(),
+                position: Point3::new(1.0, 2.0, 3.0),
+                orientation: UnitQuaternion::from_euler_angles(0.3, 0.2, 0.1),
+            },
+        });
+
+        let path = std::env::temp_dir().join(format!("duck_inputs_{}.txt", std::process::id()));
+        write_input_recording(&path, &recording).unwrap();
+        let read = read_input_recording(&path);
+        fs::remove_file(&path).unwrap();
+        let read = read.unwrap();
+
+        assert_eq!(
+            (read.seed, read.width, read.height, read.grid_size),
+            (7, 320, 240, 128)
+        );
+        assert_eq!(read.scene_file, recording.scene_file);
+        let (frame, expected) = (&read.frames[0], &recording.frames[0]);
+        assert_eq!(frame.dt, expected.dt);
+        assert_eq!(
+            (frame.rain, frame.reflection_enabled),
+            (expected.rain, expected.reflection_enabled)
+        );
+        assert_eq!(frame.ducks[0].tint, expected.ducks[0].tint);
+        assert_eq!(frame.disturbances, expected.disturbances);
+        assert_eq!(frame.destinations, expected.destinations);
+        assert_eq!(frame.camera.orientation, expected.camera.orientation);
+    }
+}
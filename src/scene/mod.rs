@@ -0,0 +1,3 @@
+pub mod read_scene;
+#[allow(clippy::module_inception)]
+pub mod scene;
@@ -0,0 +1,24 @@
+use std::fs;
+use std::path::Path;
+
+use crate::scene::scene::Scene;
+
+/// Reads a TOML scene file. The error lists everything wrong with the file: a syntax
+/// error or unknown or mistyped key with its location, or every out-of-range value.
+pub fn read_scene(path: &Path) -> Result<Scene, String> {
+    let content = fs::read_to_string(path)
+        .map_err(|error| format!("cannot read scene {}: {}", path.display(), error))?;
+    let scene: Scene = toml::from_str(&content)
+        .map_err(|error| format!("invalid scene {}:\n{}", path.display(), error))?;
+
+    let errors = scene.validate();
+    if !errors.is_empty() {
+        return Err(format!(
+            "invalid scene {}:\n  {}",
+            path.display(),
+            errors.join("\n  ")
+        ));
+    }
+
+    Ok(scene)
+}
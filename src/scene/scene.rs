@@ -0,0 +1,224 @@
+use std::path::PathBuf;
+
+use serde::Deserialize;
+
+use crate::ducks::flock::POOL_LIMIT;
+use crate::water::water_normal_computer::is_valid_grid_size;
+
+/// Describes what is loaded into the pool at startup. Every field has a default, so
+/// a scene file only needs to list what it changes.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Scene {
+    pub pool: PoolDescription,
+    pub environment: EnvironmentDescription,
+    pub objects: Vec<ObjectDescription>,
+    pub ducks: DuckDescription,
+    pub water: WaterDescription,
+    pub camera: CameraDescription,
+}
+
+impl Default for Scene {
+    fn default() -> Self {
+        Self {
+            pool: PoolDescription::default(),
+            environment: EnvironmentDescription::default(),
+            objects: vec![ObjectDescription::default()],
+            ducks: DuckDescription::default(),
+            water: WaterDescription::default(),
+            camera: CameraDescription::default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PoolDescription {
+    pub scale: f32,
+}
+
+impl Default for PoolDescription {
+    fn default() -> Self {
+        Self { scale: 5.0 }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct EnvironmentDescription {
+    pub walls: PathBuf,
+    pub sky: PathBuf,
+    pub sand: PathBuf,
+}
+
+impl Default for EnvironmentDescription {
+    fn default() -> Self {
+        Self {
+            walls: PathBuf::from("textures/vulkan.jpg"),
+            sky: PathBuf::from("textures/sky.jpg"),
+            sand: PathBuf::from("textures/sand.jpg"),
+        }
+    }
+}
+
+/// A floating object drawn with its own mesh and texture. Its path starts at `start`
+/// and passes the `waypoints` in order before wandering randomly.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ObjectDescription {
+    pub mesh: PathBuf,
+    pub texture: PathBuf,
+    pub speed: f32,
+    pub scale: f32,
+    pub tint: [f32; 3],
+    pub start: [f32; 2],
+    pub waypoints: Vec<[f32; 2]>,
+}
+
+impl Default for ObjectDescription {
+    fn default() -> Self {
+        Self {
+            mesh: PathBuf::from("meshes/duck.txt"),
+            texture: PathBuf::from("textures/ducktex.jpg"),
+            speed: 0.01,
+            scale: 0.005,
+            tint: [1.0, 1.0, 1.0],
+            start: [0.0, 0.0],
+            waypoints: vec![],
+        }
+    }
+}
+
+/// The random ducks added after the objects. The benchmark ducks share their mesh and
+/// texture.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DuckDescription {
+    pub mesh: PathBuf,
+    pub texture: PathBuf,
+    pub count: usize,
+}
+
+impl Default for DuckDescription {
+    fn default() -> Self {
+        Self {
+            mesh: PathBuf::from("meshes/duck.txt"),
+            texture: PathBuf::from("textures/ducktex.jpg"),
+            count: 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct WaterDescription {
+    /// The water height relative to the pool, between -0.9 and 0.9.
+    pub height: f32,
+    pub wave_speed: f32,
+    pub rain: bool,
+    /// The number of simulation cells along each side of the pool.
+    pub grid_size: u32,
+}
+
+impl Default for WaterDescription {
+    fn default() -> Self {
+        Self {
+            height: 0.0,
+            wave_speed: 1.0,
+            rain: true,
+            grid_size: 256,
+        }
+    }
+}
+
+/// The initial pose of the orbit camera, with angles in degrees.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CameraDescription {
+    pub target: [f32; 3],
+    pub distance: f32,
+    pub yaw: f32,
+    pub pitch: f32,
+}
+
+impl Default for CameraDescription {
+    fn default() -> Self {
+        Self {
+            target: [0.0, 0.0, 0.0],
+            distance: 4.0,
+            yaw: 0.0,
+            pitch: 0.0,
+        }
+    }
+}
+
+impl Scene {
+    /// Returns a description of every value outside of its allowed range.
+    pub fn validate(&self) -> Vec<String> {
+        let mut errors = vec![];
+        let mut check = |valid: bool, message: &str| {
+            if !valid {
+                errors.push(message.to_string());
+            }
+        };
+
+        check(self.pool.scale > 0.0, "pool.scale must be positive");
+        check(
+            (1..=64).contains(&self.get_duck_count()),
+            "objects and ducks.count must add up to between 1 and 64",
+        );
+        let limit = POOL_LIMIT * self.pool.scale;
+        let inside = |point: &[f32; 2]| point.iter().all(|c| (-limit..=limit).contains(c));
+        for (i, object) in self.objects.iter().enumerate() {
+            check(
+                (0.0..=0.1).contains(&object.speed),
+                &format!("objects[{}].speed must be between 0 and 0.1", i),
+            );
+            check(
+                (0.001..=0.01).contains(&object.scale),
+                &format!("objects[{}].scale must be between 0.001 and 0.01", i),
+            );
+            check(
+                object.tint.iter().all(|c| (0.0..=1.0).contains(c)),
+                &format!("objects[{}].tint components must be between 0 and 1", i),
+            );
+            check(
+                inside(&object.start),
+                &format!(
+                    "objects[{}].start must be within {} of the pool center",
+                    i, limit
+                ),
+            );
+            check(
+                object.waypoints.iter().all(inside),
+                &format!(
+                    "objects[{}].waypoints must be within {} of the pool center",
+                    i, limit
+                ),
+            );
+        }
+        check(
+            (-0.9..=0.9).contains(&self.water.height),
+            "water.height must be between -0.9 and 0.9",
+        );
+        check(
+            self.water.wave_speed > 0.0 && self.water.wave_speed <= 1.0,
+            "water.wave_speed must be greater than 0 and at most 1",
+        );
+        check(
+            is_valid_grid_size(self.water.grid_size),
+            "water.grid_size must be a multiple of 8 between 64 and 2048",
+        );
+        check(
+            self.camera.distance > 0.0,
+            "camera.distance must be positive",
+        );
+
+        errors
+    }
+
+    /// The number of floating objects and random ducks together.
+    pub fn get_duck_count(&self) -> usize {
+        self.objects.len() + self.ducks.count
+    }
+}
@@ -1,4 +1,7 @@
-mod vertex;
+pub mod vertex;
+#[allow(clippy::module_inception)]
 pub mod water;
 pub mod water_drawer;
 pub mod water_normal_computer;
+pub mod water_normal_renderer;
+pub mod water_simulation;
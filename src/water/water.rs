@@ -1,4 +1,4 @@
-use glium::glutin::surface::WindowSurface;
+use glium::backend::Facade;
 use glium::{IndexBuffer, VertexBuffer};
 
 use crate::water::vertex::Vertex;
@@ -10,7 +10,7 @@ pub struct Water {
 }
 
 impl Water {
-    pub fn new(display: &glium::Display<WindowSurface>) -> Self {
+    pub fn new(display: &impl Facade) -> Self {
         let vertices = vec![
             Vertex::new([-1.0, 0.0, -1.0], [0.0, 0.0]),
             Vertex::new([1.0, 0.0, -1.0], [1.0, 0.0]),
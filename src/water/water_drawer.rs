@@ -1,185 +1,100 @@
-use glium::glutin::surface::WindowSurface;
-use glium::{uniform, Display, DrawParameters, Frame, Program, Surface, Texture2d};
-use nalgebra::{Matrix4, Point3};
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
 
+use glium::backend::{Context, Facade};
+use glium::texture::RawImage2d;
+use glium::{uniform, DrawParameters, Program, Surface, Texture2d};
+use nalgebra::Matrix4;
+
+use crate::assets::asset_manager::AssetManager;
+use crate::assets::shader_error::ShaderError;
+use crate::renderer::frame_context::FrameContext;
+use crate::renderer::render_pass::RenderPass;
+use crate::renderer::render_target::RenderTarget;
 use crate::water::water::Water;
 
+const VERTEX_SHADER: &str = "shaders/water_vertex.glsl";
+const FRAGMENT_SHADER: &str = "shaders/water_fragment.glsl";
+
+/// How far the water normals push the reflection lookup, in screen fractions.
+const REFLECTION_DISTORTION: f32 = 0.02;
+
 pub struct WaterDrawer {
+    water: Water,
     program: Program,
     drawing_parameters: DrawParameters<'static>,
+    /// Bound as the reflection when the frame has none, since samplers need a texture.
+    empty_reflection: Texture2d,
 }
 
 impl WaterDrawer {
-    pub fn new(display: &Display<WindowSurface>) -> Self {
-        let vertex_shader_src = r#"
-            #version 460 core
-    
-            in vec3 position;
-            in vec2 tex_coords;
-            
-            out vec3 local_position;
-            out vec3 world_position;
-            out vec2 v_tex_coords;
-            
-            uniform mat4 perspective;
-            uniform mat4 view;
-            uniform mat4 model;
-            uniform float height;
-    
-            void main() {
-                v_tex_coords = tex_coords;
-                local_position = position;
-                local_position.y = height;
-                vec4 world_position4 = model * vec4(local_position, 1.0);
-                world_position = world_position4.xyz;
-                gl_Position = perspective * view * world_position4;
-            }
-        "#;
-
-        let fragment_shader_src = r#"
-            #version 460 core
-            
-            in vec3 local_position;
-            in vec3 world_position;
-            in vec2 v_tex_coords;
-            
-            out vec4 color;
-
-            uniform sampler2D tex1;
-            uniform sampler2D tex2;
-            uniform sampler2D tex3;
-            uniform vec3 camera_position;
-            uniform sampler2D normal_tex;
-            
-            vec3 intersect_ray(vec3 p, vec3 v) {
-                vec3 t_m = (vec3(-1, -1, -1) - p) / v;
-                vec3 t_p = (vec3(1, 1, 1) - p) / v;
-                vec3 t = max(t_m, t_p);
-                float a = min(t.x, min(t.y, t.z));
-                return p + a * v;
-            }
-
-            float fresnel(vec3 v, vec3 n) {
-                float co = max(dot(v, n), 0);
-                float F0 = 0.14;
-                return F0 + (1 - F0) * pow(1 - co, 5);
-            }
-            
-            vec4 get_color(vec3 p, vec3 v) {
-                vec3 r = intersect_ray(p, v);
-                float ax = abs(r.x);
-                float ay = abs(r.y);
-                float az = abs(r.z);
-                if (ax > ay && ax > az) {
-                    if (r.x > 0) {
-                        float tx = r.z / 2.0 + 0.5;
-                        float ty = -r.y / 2.0 + 0.5;
-                        return texture(tex1, vec2(tx, ty));
-                    } else {
-                        float tx = r.z / 2.0 + 0.5;
-                        float ty = -r.y / 2.0 + 0.5;
-                        return texture(tex1, vec2(tx, ty));
-                    }
-                } else if (ay > ax && ay > az) {
-                    if (r.y > 0) {
-                        float tx = r.x / 2.0 + 0.5;
-                        float ty = -r.z / 2.0 + 0.5;
-                        return texture(tex2, vec2(tx, ty));
-                    } else {
-                        float tx = r.x / 2.0 + 0.5;
-                        float ty = -r.z / 2.0 + 0.5;
-                        return texture(tex3, vec2(tx, ty));
-                    }
-                } else {
-                    if (r.z > 0) {
-                        float tx = r.x / 2.0 + 0.5;
-                        float ty = -r.y / 2.0 + 0.5;
-                        return texture(tex1, vec2(tx, ty));
-                    } else {
-                        float tx = r.x / 2.0 + 0.5;
-                        float ty = -r.y / 2.0 + 0.5;
-                        return texture(tex1, vec2(tx, ty));
-                    }
-                }
-                return vec4(0, 1, 0, 1);
-            }
-            
-            void main() {
-                vec3 view_vector = normalize(camera_position - world_position);
-                vec3 normal = normalize(texture(normal_tex, v_tex_coords).xyz);
-                float n1n2 = 3.0/4.0;
-                
-                bool below = dot(view_vector, normal) < 0;
-                if (below) {
-                    normal.y = -normal.y;
-                    n1n2 = 1.0 / n1n2;
-                }
+    pub fn new(display: &impl Facade, assets: &mut AssetManager) -> Result<Self, ShaderError> {
+        let program = assets.load_program(
+            display,
+            Path::new(VERTEX_SHADER),
+            Path::new(FRAGMENT_SHADER),
+        )?;
 
-                vec3 reflected = reflect(-view_vector, normal);
-                vec3 refracted = refract(-view_vector, normal, n1n2);
-                float fresnel_value = fresnel(view_vector, normal);
-                
-                vec4 reflected_color = get_color(local_position, reflected);
-                vec4 refracted_color = get_color(local_position, refracted);
-                
-                if (!all(isnan(refracted))) {
-                    color = mix(refracted_color, reflected_color, fresnel_value);
-                } else {
-                    color = reflected_color;
-                }
-            }
-        "#;
-
-        let program =
-            Program::from_source(display, vertex_shader_src, fragment_shader_src, None).unwrap();
-
-        let mut drawing_parameters = DrawParameters::default();
-        drawing_parameters.depth = glium::Depth {
-            test: glium::draw_parameters::DepthTest::IfLess,
-            write: true,
+        let drawing_parameters = DrawParameters {
+            depth: glium::Depth {
+                test: glium::draw_parameters::DepthTest::IfLess,
+                write: true,
+                ..Default::default()
+            },
+            backface_culling: glium::draw_parameters::BackfaceCullingMode::CullingDisabled,
             ..Default::default()
         };
-        drawing_parameters.backface_culling =
-            glium::draw_parameters::BackfaceCullingMode::CullingDisabled;
 
-        Self {
+        Ok(Self {
+            water: Water::new(display),
             program,
             drawing_parameters,
-        }
+            empty_reflection: Texture2d::new(
+                display,
+                RawImage2d::from_raw_rgba(vec![0u8; 4], (1, 1)),
+            )
+            .unwrap(),
+        })
     }
+}
 
-    pub fn draw(
-        &self,
-        target: &mut Frame,
-        water: &Water,
-        perspective: &Matrix4<f32>,
-        view: &Matrix4<f32>,
-        model: &Matrix4<f32>,
-        camera_position: &Point3<f32>,
-        water_height: f32,
-        texture1: &Texture2d,
-        texture2: &Texture2d,
-        texture3: &Texture2d,
-        normal_tex: &Texture2d,
-    ) {
+/// Draws the water surface, reflecting and refracting the environment and reflecting
+/// the ducks when the frame has a reflection.
+impl RenderPass for WaterDrawer {
+    fn render(&self, _context: &Rc<Context>, target: &mut RenderTarget, frame: &FrameContext) {
+        let model = Matrix4::new_scaling(frame.scale);
+        let camera_position = frame.camera_position;
         target
             .draw(
-                &water.vertex_buffer,
-                &water.index_buffer,
+                &self.water.vertex_buffer,
+                &self.water.index_buffer,
                 &self.program,
                 &uniform! {
-                    perspective: perspective.data.0,
-                    view: view.data.0,
+                    perspective: frame.perspective.data.0,
+                    view: frame.view.data.0,
                     model: model.data.0,
                     camera_position: [camera_position.x, camera_position.y, camera_position.z],
-                    height: water_height,
-                    tex1: texture1,
-                    tex2: texture2,
-                    tex3: texture3,
-                    normal_tex: normal_tex,
+                    height: frame.water_height,
+                    tex1: frame.environment.walls,
+                    tex2: frame.environment.sky,
+                    tex3: frame.environment.sand,
+                    normal_tex: frame.water_normals,
+                    reflection_tex: frame.reflection.unwrap_or(&self.empty_reflection),
+                    reflection_enabled: frame.reflection.is_some(),
+                    reflection_distortion: REFLECTION_DISTORTION,
                 },
                 &self.drawing_parameters,
             )
             .unwrap();
     }
+
+    fn reload(&mut self, context: &Rc<Context>, assets: &mut AssetManager, changed: &[PathBuf]) {
+        assets.reload_program(
+            context,
+            &mut self.program,
+            Path::new(VERTEX_SHADER),
+            Path::new(FRAGMENT_SHADER),
+            changed,
+        );
+    }
 }
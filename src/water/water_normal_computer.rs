@@ -1,7 +1,18 @@
-use glium::glutin::surface::WindowSurface;
+use std::path::{Path, PathBuf};
+
+use glium::backend::Facade;
 use glium::program::ComputeShader;
 use glium::uniforms::{ImageUnitAccess, ImageUnitFormat};
-use glium::{uniform, Display, Texture2d};
+use glium::{uniform, Texture2d};
+use nalgebra::Point2;
+
+use crate::assets::asset_manager::AssetManager;
+use crate::assets::shader_error::ShaderError;
+
+const HEIGHT_SHADER: &str = "shaders/water_height_compute.glsl";
+const SWAP_SHADER: &str = "shaders/water_swap_compute.glsl";
+const NORMAL_SHADER: &str = "shaders/water_normal_compute.glsl";
+const BEND_SHADER: &str = "shaders/water_bend_compute.glsl";
 
 pub struct WaterNormalComputer {
     height_compute_shader: ComputeShader,
@@ -11,19 +22,27 @@ pub struct WaterNormalComputer {
     tex1: Texture2d,
     tex2: Texture2d,
     pub normal_tex: Texture2d,
+    size: u32,
     a: f32,
     b: f32,
     dt: f32,
 }
 
 impl WaterNormalComputer {
-    pub fn new(display: &Display<WindowSurface>) -> Self {
+    /// Creates the solver on a `size` by `size` grid for waves travelling with
+    /// `wave_speed`, which must be at most 1 for the simulation to stay stable.
+    pub fn new(
+        display: &impl Facade,
+        assets: &mut AssetManager,
+        size: u32,
+        wave_speed: f32,
+    ) -> Result<Self, ShaderError> {
         let tex1 = Texture2d::empty_with_format(
             display,
             glium::texture::UncompressedFloatFormat::F32,
             glium::texture::MipmapsOption::NoMipmap,
-            256,
-            256,
+            size,
+            size,
         )
         .unwrap();
 
@@ -31,8 +50,8 @@ impl WaterNormalComputer {
             display,
             glium::texture::UncompressedFloatFormat::F32,
             glium::texture::MipmapsOption::NoMipmap,
-            256,
-            256,
+            size,
+            size,
         )
         .unwrap();
 
@@ -40,120 +59,24 @@ impl WaterNormalComputer {
             display,
             glium::texture::UncompressedFloatFormat::U8U8U8U8,
             glium::texture::MipmapsOption::NoMipmap,
-            256,
-            256,
-        )
-        .unwrap();
-
-        let height_compute_shader = ComputeShader::from_source(
-            display,
-            r#"\
-            #version 460 core
-            layout(local_size_x = 8, local_size_y = 4, local_size_z = 1) in;
-
-            layout(r32f) readonly uniform image2D tex1;
-            layout(r32f) uniform image2D tex2;
-
-            uniform float A;
-            uniform float B;
-
-            float get_d(vec2 i) {
-                float lx = max(i.x / 128.0f, 2.0f - i.x / 128.0f);
-                float ly = max(i.y / 128.0f, 2.0f - i.y / 128.0f);
-                float l = max(lx, ly);
-                return 0.95 * min(1.0, l / 0.2);
-            }
-
-            void main() {
-                ivec2 i = ivec2(gl_GlobalInvocationID.x, gl_GlobalInvocationID.y);
-                float z1 = imageLoad(tex1, ivec2(i.x, i.y + 1)).x;
-                float z2 = imageLoad(tex1, ivec2(i.x, i.y - 1)).x;
-                float z3 = imageLoad(tex1, ivec2(i.x + 1, i.y)).x;
-                float z4 = imageLoad(tex1, ivec2(i.x - 1, i.y)).x;
-                float z5 = imageLoad(tex1, ivec2(i.x, i.y)).x;
-                float z6 = imageLoad(tex2, ivec2(i.x, i.y)).x;
-
-                float d = get_d(i);
-
-                float c = d * (A * (z1 + z2 + z3 + z4) + B * z5 - z6);
-                imageStore(tex2, i, vec4(c, 0, 0, 0));
-            }
-            "#,
-        )
-        .unwrap();
-
-        let swap_compute_shader = ComputeShader::from_source(
-            display,
-            r#"\
-            #version 460 core
-            layout(local_size_x = 8, local_size_y = 4, local_size_z = 1) in;
-
-            layout(r32f) uniform image2D tex1;
-            layout(r32f) uniform image2D tex2;
-
-            void main() {
-                ivec2 i = ivec2(gl_GlobalInvocationID.x, gl_GlobalInvocationID.y);
-                vec4 c1 = imageLoad(tex1, i);
-                vec4 c2 = imageLoad(tex2, i);
-                imageStore(tex1, i, c2);
-                imageStore(tex2, i, c1);
-            }
-            "#,
+            size,
+            size,
         )
         .unwrap();
 
-        let normal_compute_shader = ComputeShader::from_source(
-            display,
-            r#"\
-            #version 460 core
-            layout(local_size_x = 8, local_size_y = 4, local_size_z = 1) in;
-
-            layout(r32f) readonly uniform image2D tex1;
-            layout(rgba8) writeonly uniform image2D normal_tex;
-
-            void main() {
-                ivec2 i = ivec2(gl_GlobalInvocationID.x, gl_GlobalInvocationID.y);
-                ivec2 ix1 = ivec2(min(i.x + 1, 255), i.y);
-                ivec2 ix2 = ivec2(max(i.x - 1, 0), i.y);
-                ivec2 iy1 = ivec2(i.x, min(i.y + 1, 255));
-                ivec2 iy2 = ivec2(i.x, max(i.y - 1, 0));
-                float y1 = imageLoad(tex1, ix1).x - imageLoad(tex1, ix2).x;
-                float y2 = imageLoad(tex1, iy1).x - imageLoad(tex1, iy2).x;
-                vec3 v1 = vec3(2.0 / 256.0, y1, 0.0);
-                vec3 v2 = vec3(0.0, y2, 2.0 / 256.0);
-                vec3 n = normalize(cross(v2.xyz, v1.xyz));
-                imageStore(normal_tex, i, vec4(n, 0.0));
-            }
-            "#,
-        )
-        .unwrap();
+        let height_compute_shader =
+            assets.load_compute_shader(display, Path::new(HEIGHT_SHADER))?;
 
-        let bend_compute_shader = ComputeShader::from_source(
-            display,
-            r#"\
-            #version 460 core
-            layout(local_size_x = 1, local_size_y = 1, local_size_z = 1) in;
+        let swap_compute_shader = assets.load_compute_shader(display, Path::new(SWAP_SHADER))?;
 
-            layout(r32f) writeonly uniform image2D tex1;
-            uniform int x;
-            uniform int y;
+        let normal_compute_shader =
+            assets.load_compute_shader(display, Path::new(NORMAL_SHADER))?;
 
-            void main() {
-                ivec2 i = ivec2(x, y);
-                imageStore(tex1, i, vec4(-0.25, 0, 0, 0));
-            }
-            "#,
-        )
-        .unwrap();
+        let bend_compute_shader = assets.load_compute_shader(display, Path::new(BEND_SHADER))?;
 
-        let n = 256f32;
-        let h = 2.0 / (n - 1.0);
-        let c = 1f32;
-        let dt = 1.0 / n;
-        let a = c * c * dt * dt / (h * h);
-        let b = 2.0 - 4.0 * a;
+        let (a, b, dt) = get_wave_coefficients(size, wave_speed);
 
-        Self {
+        Ok(Self {
             height_compute_shader,
             swap_compute_shader,
             normal_compute_shader,
@@ -161,10 +84,44 @@ impl WaterNormalComputer {
             tex1,
             tex2,
             normal_tex,
+            size,
             a,
             b,
             dt,
-        }
+        })
+    }
+
+    /// Rebuilds the compute shaders whose files changed.
+    pub fn reload(
+        &mut self,
+        display: &impl Facade,
+        assets: &mut AssetManager,
+        changed: &[PathBuf],
+    ) {
+        assets.reload_compute_shader(
+            display,
+            &mut self.height_compute_shader,
+            Path::new(HEIGHT_SHADER),
+            changed,
+        );
+        assets.reload_compute_shader(
+            display,
+            &mut self.swap_compute_shader,
+            Path::new(SWAP_SHADER),
+            changed,
+        );
+        assets.reload_compute_shader(
+            display,
+            &mut self.normal_compute_shader,
+            Path::new(NORMAL_SHADER),
+            changed,
+        );
+        assets.reload_compute_shader(
+            display,
+            &mut self.bend_compute_shader,
+            Path::new(BEND_SHADER),
+            changed,
+        );
     }
 
     pub fn compute(&self) {
@@ -185,9 +142,10 @@ impl WaterNormalComputer {
                 tex2: tex2_unit,
                 A: self.a,
                 B: self.b,
+                size: self.size as i32,
             },
-            32,
-            64,
+            self.size / 8,
+            self.size / 4,
             1,
         );
 
@@ -207,8 +165,8 @@ impl WaterNormalComputer {
                 tex1: tex1_unit,
                 tex2: tex2_unit,
             },
-            32,
-            64,
+            self.size / 8,
+            self.size / 4,
             1,
         );
 
@@ -227,14 +185,19 @@ impl WaterNormalComputer {
             uniform! {
                 tex1: tex1_unit,
                 normal_tex: normal_unit,
+                size: self.size as i32,
             },
-            32,
-            64,
+            self.size / 8,
+            self.size / 4,
             1,
         );
     }
 
     pub fn bend(&self, x: i32, y: i32) {
+        self.bend_with_strength(x, y, 1.0);
+    }
+
+    pub fn bend_with_strength(&self, x: i32, y: i32, strength: f32) {
         let tex1_unit = self
             .tex1
             .image_unit(ImageUnitFormat::R32F)
@@ -246,6 +209,7 @@ impl WaterNormalComputer {
                 tex1: tex1_unit,
                 x: x,
                 y: y,
+                strength: strength,
             },
             1,
             1,
@@ -253,7 +217,37 @@ impl WaterNormalComputer {
         );
     }
 
+    pub fn bend_at(&self, position: Point2<f32>, pool_scale: f32, strength: f32) {
+        let half_size = self.size as f32 / 2.0;
+        self.bend_with_strength(
+            (position.x * half_size / pool_scale + half_size) as i32,
+            (position.y * half_size / pool_scale + half_size) as i32,
+            strength,
+        );
+    }
+
+    pub fn get_size(&self) -> u32 {
+        self.size
+    }
+
     pub fn get_dt(&self) -> f32 {
         self.dt
     }
 }
+
+/// Returns the solver's A and B coefficients and its time step for a `size` by `size`
+/// grid spanning the pool.
+pub fn get_wave_coefficients(size: u32, wave_speed: f32) -> (f32, f32, f32) {
+    let n = size as f32;
+    let h = 2.0 / (n - 1.0);
+    let c = wave_speed;
+    let dt = 1.0 / n;
+    let a = c * c * dt * dt / (h * h);
+    let b = 2.0 - 4.0 * a;
+    (a, b, dt)
+}
+
+/// The grid must fill whole 8 by 4 work groups and stay within sensible memory limits.
+pub fn is_valid_grid_size(size: u32) -> bool {
+    size.is_multiple_of(8) && (64..=2048).contains(&size)
+}
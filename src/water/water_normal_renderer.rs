@@ -0,0 +1,229 @@
+use std::borrow::Cow;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+use glium::backend::{Context, Facade};
+use glium::framebuffer::SimpleFrameBuffer;
+use glium::index::{NoIndices, PrimitiveType};
+use glium::texture::{ClientFormat, RawImage2d, UncompressedFloatFormat};
+use glium::uniforms::{MagnifySamplerFilter, MinifySamplerFilter, Sampler};
+use glium::{implement_vertex, uniform, Program, Rect, Surface, Texture2d, VertexBuffer};
+use nalgebra::Point2;
+
+use crate::assets::asset_manager::AssetManager;
+use crate::assets::shader_error::ShaderError;
+use crate::water::water_normal_computer::get_wave_coefficients;
+
+const VERTEX_SHADER: &str = "shaders/water_simulation_vertex.glsl";
+const HEIGHT_SHADER: &str = "shaders/water_height_fragment.glsl";
+const NORMAL_SHADER: &str = "shaders/water_normal_fragment.glsl";
+
+#[derive(Debug, Copy, Clone)]
+struct QuadVertex {
+    position: [f32; 2],
+}
+
+implement_vertex!(QuadVertex, position);
+
+/// The same solver as `WaterNormalComputer` for contexts without compute shaders. Each
+/// step renders the new heights from the current and previous ones into a third float
+/// texture and then rotates the three, so it only needs GL 3.3 render targets.
+pub struct WaterNormalRenderer {
+    context: Rc<Context>,
+    height_program: Program,
+    normal_program: Program,
+    quad: VertexBuffer<QuadVertex>,
+    current: Texture2d,
+    previous: Texture2d,
+    next: Texture2d,
+    pub normal_tex: Texture2d,
+    size: u32,
+    a: f32,
+    b: f32,
+    dt: f32,
+}
+
+impl WaterNormalRenderer {
+    pub fn new(
+        display: &impl Facade,
+        assets: &mut AssetManager,
+        size: u32,
+        wave_speed: f32,
+    ) -> Result<Self, ShaderError> {
+        let height_texture = || {
+            let texture = Texture2d::empty_with_format(
+                display,
+                UncompressedFloatFormat::F32,
+                glium::texture::MipmapsOption::NoMipmap,
+                size,
+                size,
+            )
+            .unwrap();
+            texture.as_surface().clear_color(0.0, 0.0, 0.0, 0.0);
+            texture
+        };
+
+        let normal_tex = Texture2d::empty_with_format(
+            display,
+            UncompressedFloatFormat::U8U8U8U8,
+            glium::texture::MipmapsOption::NoMipmap,
+            size,
+            size,
+        )
+        .unwrap();
+
+        let height_program =
+            assets.load_program(display, Path::new(VERTEX_SHADER), Path::new(HEIGHT_SHADER))?;
+
+        let normal_program =
+            assets.load_program(display, Path::new(VERTEX_SHADER), Path::new(NORMAL_SHADER))?;
+
+        let quad = VertexBuffer::new(
+            display,
+            &[
+                QuadVertex {
+                    position: [-1.0, -1.0],
+                },
+                QuadVertex {
+                    position: [1.0, -1.0],
+                },
+                QuadVertex {
+                    position: [-1.0, 1.0],
+                },
+                QuadVertex {
+                    position: [1.0, 1.0],
+                },
+            ],
+        )
+        .unwrap();
+
+        let (a, b, dt) = get_wave_coefficients(size, wave_speed);
+
+        Ok(Self {
+            context: display.get_context().clone(),
+            height_program,
+            normal_program,
+            quad,
+            current: height_texture(),
+            previous: height_texture(),
+            next: height_texture(),
+            normal_tex,
+            size,
+            a,
+            b,
+            dt,
+        })
+    }
+
+    /// Rebuilds the programs whose shader files changed.
+    pub fn reload(
+        &mut self,
+        display: &impl Facade,
+        assets: &mut AssetManager,
+        changed: &[PathBuf],
+    ) {
+        assets.reload_program(
+            display,
+            &mut self.height_program,
+            Path::new(VERTEX_SHADER),
+            Path::new(HEIGHT_SHADER),
+            changed,
+        );
+        assets.reload_program(
+            display,
+            &mut self.normal_program,
+            Path::new(VERTEX_SHADER),
+            Path::new(NORMAL_SHADER),
+            changed,
+        );
+    }
+
+    pub fn compute(&mut self) {
+        let mut target = SimpleFrameBuffer::new(&self.context, &self.next).unwrap();
+        target
+            .draw(
+                &self.quad,
+                NoIndices(PrimitiveType::TriangleStrip),
+                &self.height_program,
+                &uniform! {
+                    current: get_texel_sampler(&self.current),
+                    previous: get_texel_sampler(&self.previous),
+                    A: self.a,
+                    B: self.b,
+                    size: self.size as i32,
+                },
+                &Default::default(),
+            )
+            .unwrap();
+
+        std::mem::swap(&mut self.previous, &mut self.current);
+        std::mem::swap(&mut self.current, &mut self.next);
+
+        let mut target = SimpleFrameBuffer::new(&self.context, &self.normal_tex).unwrap();
+        target
+            .draw(
+                &self.quad,
+                NoIndices(PrimitiveType::TriangleStrip),
+                &self.normal_program,
+                &uniform! {
+                    current: get_texel_sampler(&self.current),
+                    size: self.size as i32,
+                },
+                &Default::default(),
+            )
+            .unwrap();
+    }
+
+    pub fn bend(&self, x: i32, y: i32) {
+        self.bend_with_strength(x, y, 1.0);
+    }
+
+    /// Sets the height of one cell. Cells outside the grid are ignored, like image
+    /// stores outside the image are in the compute path.
+    pub fn bend_with_strength(&self, x: i32, y: i32, strength: f32) {
+        let size = self.size as i32;
+        if !(0..size).contains(&x) || !(0..size).contains(&y) {
+            return;
+        }
+
+        let cell = RawImage2d {
+            data: Cow::Owned(vec![-0.25 * strength]),
+            width: 1,
+            height: 1,
+            format: ClientFormat::F32,
+        };
+        self.current.write(
+            Rect {
+                left: x as u32,
+                bottom: y as u32,
+                width: 1,
+                height: 1,
+            },
+            cell,
+        );
+    }
+
+    pub fn bend_at(&self, position: Point2<f32>, pool_scale: f32, strength: f32) {
+        let half_size = self.size as f32 / 2.0;
+        self.bend_with_strength(
+            (position.x * half_size / pool_scale + half_size) as i32,
+            (position.y * half_size / pool_scale + half_size) as i32,
+            strength,
+        );
+    }
+
+    pub fn get_size(&self) -> u32 {
+        self.size
+    }
+
+    pub fn get_dt(&self) -> f32 {
+        self.dt
+    }
+}
+
+fn get_texel_sampler(texture: &Texture2d) -> Sampler<'_, Texture2d> {
+    texture
+        .sampled()
+        .minify_filter(MinifySamplerFilter::Nearest)
+        .magnify_filter(MagnifySamplerFilter::Nearest)
+}
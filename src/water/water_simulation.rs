@@ -0,0 +1,92 @@
+use std::path::PathBuf;
+
+use glium::backend::Facade;
+use glium::Texture2d;
+use nalgebra::Point2;
+
+use crate::assets::asset_manager::AssetManager;
+use crate::assets::shader_error::ShaderError;
+use crate::capabilities::capabilities::WaterPath;
+use crate::water::water_normal_computer::WaterNormalComputer;
+use crate::water::water_normal_renderer::WaterNormalRenderer;
+
+/// The water solver, running in compute shaders or in fragment shaders depending on the
+/// path the context supports.
+#[allow(clippy::large_enum_variant)]
+pub enum WaterSimulation {
+    Compute(WaterNormalComputer),
+    Fragment(WaterNormalRenderer),
+}
+
+impl WaterSimulation {
+    pub fn new(
+        display: &impl Facade,
+        assets: &mut AssetManager,
+        path: WaterPath,
+        size: u32,
+        wave_speed: f32,
+    ) -> Result<Self, ShaderError> {
+        match path {
+            WaterPath::Compute => Ok(Self::Compute(WaterNormalComputer::new(
+                display, assets, size, wave_speed,
+            )?)),
+            WaterPath::Fragment => Ok(Self::Fragment(WaterNormalRenderer::new(
+                display, assets, size, wave_speed,
+            )?)),
+        }
+    }
+
+    pub fn reload(
+        &mut self,
+        display: &impl Facade,
+        assets: &mut AssetManager,
+        changed: &[PathBuf],
+    ) {
+        match self {
+            Self::Compute(computer) => computer.reload(display, assets, changed),
+            Self::Fragment(renderer) => renderer.reload(display, assets, changed),
+        }
+    }
+
+    pub fn compute(&mut self) {
+        match self {
+            Self::Compute(computer) => computer.compute(),
+            Self::Fragment(renderer) => renderer.compute(),
+        }
+    }
+
+    pub fn bend(&self, x: i32, y: i32) {
+        match self {
+            Self::Compute(computer) => computer.bend(x, y),
+            Self::Fragment(renderer) => renderer.bend(x, y),
+        }
+    }
+
+    pub fn bend_at(&self, position: Point2<f32>, pool_scale: f32, strength: f32) {
+        match self {
+            Self::Compute(computer) => computer.bend_at(position, pool_scale, strength),
+            Self::Fragment(renderer) => renderer.bend_at(position, pool_scale, strength),
+        }
+    }
+
+    pub fn get_normal_texture(&self) -> &Texture2d {
+        match self {
+            Self::Compute(computer) => &computer.normal_tex,
+            Self::Fragment(renderer) => &renderer.normal_tex,
+        }
+    }
+
+    pub fn get_size(&self) -> u32 {
+        match self {
+            Self::Compute(computer) => computer.get_size(),
+            Self::Fragment(renderer) => renderer.get_size(),
+        }
+    }
+
+    pub fn get_dt(&self) -> f32 {
+        match self {
+            Self::Compute(computer) => computer.get_dt(),
+            Self::Fragment(renderer) => renderer.get_dt(),
+        }
+    }
+}
@@ -0,0 +1,2 @@
+pub mod windowed_context;
+pub mod windowed_runner;
@@ -0,0 +1,74 @@
+use std::num::NonZeroU32;
+
+use glium::glutin::context::ContextAttributesBuilder;
+use glium::glutin::display::GetGlDisplay;
+use glium::glutin::prelude::*;
+use glium::glutin::surface::{SurfaceAttributesBuilder, SwapInterval, WindowSurface};
+use glium::Display;
+use glutin_winit::DisplayBuilder;
+use raw_window_handle::HasRawWindowHandle;
+use winit::dpi::PhysicalSize;
+use winit::event_loop::EventLoop;
+use winit::window::{Fullscreen, Window, WindowBuilder};
+
+use crate::cli::options::Options;
+
+/// Opens the window with the size, fullscreen and vsync settings from the options and
+/// creates a glium display for it.
+pub fn create_windowed_context(
+    event_loop: &EventLoop<()>,
+    options: &Options,
+) -> (Window, Display<WindowSurface>) {
+    let mut window_builder = WindowBuilder::new()
+        .with_title("Duck")
+        .with_inner_size(PhysicalSize::new(options.width, options.height));
+    if options.fullscreen {
+        window_builder = window_builder.with_fullscreen(Some(Fullscreen::Borderless(None)));
+    }
+
+    let (window, config) = DisplayBuilder::new()
+        .with_window_builder(Some(window_builder))
+        .build(event_loop, Default::default(), |mut configs| {
+            configs.next().unwrap()
+        })
+        .unwrap();
+    let window = window.unwrap();
+
+    // The options reject a zero size, but the window may still report one, for example
+    // when it opens minimized, and the surface needs at least one pixel.
+    let (width, height): (u32, u32) = window.inner_size().into();
+    let surface_attributes = SurfaceAttributesBuilder::<WindowSurface>::new().build(
+        window.raw_window_handle(),
+        NonZeroU32::new(width).unwrap_or(NonZeroU32::MIN),
+        NonZeroU32::new(height).unwrap_or(NonZeroU32::MIN),
+    );
+    let surface = unsafe {
+        config
+            .display()
+            .create_window_surface(&config, &surface_attributes)
+            .unwrap()
+    };
+
+    let context_attributes =
+        ContextAttributesBuilder::new().build(Some(window.raw_window_handle()));
+    let context = unsafe {
+        config
+            .display()
+            .create_context(&config, &context_attributes)
+            .unwrap()
+    }
+    .make_current(&surface)
+    .unwrap();
+
+    let swap_interval = if options.vsync {
+        SwapInterval::Wait(NonZeroU32::new(1).unwrap())
+    } else {
+        SwapInterval::DontWait
+    };
+    if let Err(error) = surface.set_swap_interval(&context, swap_interval) {
+        eprintln!("Cannot change vsync: {}", error);
+    }
+
+    let display = Display::from_context_surface(context, surface).unwrap();
+    (window, display)
+}
@@ -0,0 +1,682 @@
+use std::process::exit;
+
+use chrono::{DateTime, Local};
+use egui::{DragValue, Slider, Widget};
+use glium::Surface;
+use nalgebra::{clamp, Point2, Point3, Vector2};
+use winit::event::ElementState::Pressed;
+use winit::event::{KeyboardInput, MouseButton, VirtualKeyCode};
+use winit::{event, event_loop};
+
+use crate::assets::asset_manager::AssetManager;
+use crate::assets::asset_paths::AssetPaths;
+use crate::camera::bookmarks::{read_bookmarks, write_bookmarks, Bookmark};
+use crate::camera::cameras::{CameraMode, Cameras};
+use crate::capabilities::capabilities::Capabilities;
+use crate::capture::recorder::Recorder;
+use crate::capture::screenshot::{
+    get_screenshot_path, save_offscreen_screenshot, save_window_screenshot, ScreenshotSettings,
+};
+use crate::cli::options::Options;
+use crate::picking::ray::pick_water;
+use crate::pool::pool::Pool;
+use crate::renderer::frame_context::FrameContext;
+use crate::renderer::render_target::RenderTarget;
+use crate::renderer::renderer::Renderer;
+use crate::replay::input_recording::{
+    get_input_recording_path, read_input_recording, write_input_recording, FrameInput,
+    InputRecording,
+};
+use crate::scene::scene::Scene;
+use crate::windowed::windowed_context::create_windowed_context;
+
+const BOOKMARKS_FILE: &str = "camera_bookmarks.txt";
+const WATCH_INTERVAL_MS: i64 = 500;
+
+/// Opens the window and runs the interactive simulation until it is closed.
+pub fn run_windowed(mut options: Options, mut scene: Scene) {
+    let replay_recording = options.replay_file.as_ref().map(|path| {
+        read_input_recording(path).unwrap_or_else(|error| {
+            eprintln!("Cannot read {}: {}", path.display(), error);
+            exit(1);
+        })
+    });
+    // A replay runs with the window size, scene and water grid it was recorded with.
+    if let Some(recording) = &replay_recording {
+        options.width = recording.width;
+        options.height = recording.height;
+        options.fullscreen = false;
+        scene = recording
+            .get_scene(options.scene_file.as_deref(), &scene)
+            .unwrap_or_else(|error| {
+                eprintln!("Cannot read the recorded scene: {}", error);
+                exit(1);
+            });
+        options.scene_file = recording.scene_file.clone();
+    }
+
+    let event_loop = winit::event_loop::EventLoopBuilder::new().build();
+    let (window, display) = create_windowed_context(&event_loop, &options);
+
+    let mut egui_glium = egui_glium::EguiGlium::new(&display, &window, &event_loop);
+
+    let seed = match &replay_recording {
+        Some(recording) => recording.seed,
+        None => options.seed,
+    };
+    let mut replay = replay_recording.map(|recording| recording.frames.into_iter());
+
+    let mut assets = AssetManager::new(
+        AssetPaths::new(options.asset_root.as_deref()),
+        options.shaders_from_disk,
+    );
+    let capabilities = Capabilities::detect(&display);
+    println!("{}", capabilities.get_report(scene.water.grid_size));
+    let mut pool =
+        Pool::new(&display, &scene, &mut assets, &capabilities, seed).unwrap_or_else(|error| {
+            eprintln!("Cannot create the scene: {}", error);
+            exit(1);
+        });
+    let mut renderer = Renderer::new(&display, &mut assets).unwrap_or_else(|error| {
+        eprintln!("Cannot create the renderer: {}", error);
+        exit(1);
+    });
+    println!("Seed: {}", seed);
+    let mut steering_enabled = false;
+    let mut steered_duck = 0usize;
+
+    let mut mouse_position = (0.0, 0.0);
+    // The replayed frames keep the recorded aspect ratio even if the window manager
+    // does not give the window its recorded size.
+    let (width, height) = match &replay {
+        Some(_) => (options.width, options.height),
+        None => window.inner_size().into(),
+    };
+    let mut cameras = Cameras::new(width, height, &scene.camera);
+    let mut followed_duck = 0usize;
+    let mut bookmark_name = String::from("Shot 1");
+    let mut bookmarks_status = String::new();
+    let mut mouse_middle_button_pressed = false;
+    let mut mouse_left_button_pressed = false;
+    let mut previous_water_hit = None;
+
+    let mut screenshot_settings = ScreenshotSettings::default();
+    let mut screenshot_requested = false;
+    let mut screenshot_status = String::new();
+
+    let mut recorder: Option<Recorder> = None;
+    let mut recording_frame_rate = 60u32;
+    let mut recording_use_ffmpeg = true;
+    let mut recording_toggle_requested = false;
+    let mut recording_status = String::new();
+
+    let mut input_recording: Option<InputRecording> = None;
+    let mut input_recording_toggle_requested = false;
+    let mut input_recording_status = String::new();
+
+    let mut previous_time = Local::now();
+    let mut average_fps = 0.0f64;
+    let mut last_watch_time = Local::now();
+
+    event_loop.run(move |event, _window_target, control_flow| {
+        let mut redraw = || {
+            let current_time = Local::now();
+            let duration = current_time - previous_time;
+            let duration_in_seconds = duration.num_microseconds().unwrap_or(1) as f64 / 1_000_000.0;
+            let fps = 1.0 / duration_in_seconds;
+            previous_time = current_time;
+            average_fps = average_fps * 0.95 + fps * 0.05;
+
+            if options.watch
+                && (current_time - last_watch_time).num_milliseconds() >= WATCH_INTERVAL_MS
+            {
+                last_watch_time = current_time;
+                let changed = assets.poll_changes();
+                for key in &changed {
+                    println!("Changed {}", key.display());
+                }
+                if !changed.is_empty() {
+                    pool.reload(&display, &mut assets, &changed);
+                    renderer.reload(&display, &mut assets, &changed);
+                }
+            }
+
+            let replay_input = replay.as_mut().and_then(Iterator::next);
+            if replay.is_some() && replay_input.is_none() {
+                replay = None;
+                input_recording_status = String::from("Replay finished");
+                let size = window.inner_size();
+                cameras.resize(size.width, size.height);
+            }
+
+            let frame_dt = match (&replay_input, &recorder) {
+                (Some(input), _) => input.dt,
+                (None, Some(recorder)) => recorder.get_frame_dt(),
+                (None, None) => duration_in_seconds as f32,
+            };
+
+            if let Some(duck) = pool.flock.ducks.get(followed_duck) {
+                cameras.chase.set_target(
+                    Point3::new(
+                        duck.position.x,
+                        pool.water_height * pool.scale,
+                        duck.position.y,
+                    ),
+                    duck.heading,
+                );
+            }
+            match &replay_input {
+                Some(input) => input.apply(&mut pool, &mut renderer, &mut cameras),
+                None => cameras.get_active_mut().update(frame_dt),
+            }
+            let camera_pose = Bookmark::from_camera(String::new(), cameras.get_active());
+
+            egui_glium.run(&window, |egui_ctx| {
+                egui::Window::new("panel").show(egui_ctx, |ui| {
+                    Slider::new(&mut pool.water_height, -0.9..=0.9)
+                        .step_by(0.05)
+                        .text("water height")
+                        .ui(ui);
+                    ui.checkbox(&mut pool.rain, "Rain");
+
+                    Slider::new(&mut pool.duck_count, 1..=64)
+                        .text("Duck count")
+                        .ui(ui);
+
+                    ui.checkbox(&mut steering_enabled, "Click to steer");
+                    if steering_enabled {
+                        Slider::new(&mut steered_duck, 0..=pool.duck_count - 1)
+                            .text("Steered duck")
+                            .ui(ui);
+                    }
+
+                    for (i, duck) in pool.flock.ducks.iter_mut().enumerate() {
+                        ui.collapsing(format!("Duck {}", i), |ui| {
+                            Slider::new(&mut duck.speed, 0.000..=0.1)
+                                .step_by(0.001)
+                                .text("Duck speed")
+                                .ui(ui);
+
+                            Slider::new(&mut duck.scale, 0.001..=0.01)
+                                .step_by(0.0005)
+                                .text("Duck scale")
+                                .ui(ui);
+
+                            ui.horizontal(|ui| {
+                                ui.color_edit_button_rgb(&mut duck.tint);
+                                ui.label("Duck tint");
+                            });
+
+                            ui.label(format!(
+                                "Duck position: ({:.1}, {:.1})",
+                                duck.position.x, duck.position.y
+                            ));
+
+                            for (j, point) in duck.path.control_points.iter().enumerate() {
+                                ui.label(format!("P[{}]: ({:.1}, {:.1})", j, point.x, point.y));
+                            }
+
+                            if let Some(destination) = duck.path.get_destination() {
+                                ui.label(format!(
+                                    "Destination: ({:.1}, {:.1})",
+                                    destination.x, destination.y
+                                ));
+                            }
+                        });
+                    }
+
+                    ui.label(format!("FPS: {:.1}", fps));
+                    ui.label(format!("Seed: {}", pool.get_seed()));
+
+                    let mut camera_mode = cameras.get_mode();
+                    ui.horizontal(|ui| {
+                        ui.label("Camera:");
+                        ui.radio_value(&mut camera_mode, CameraMode::Orbit, "Orbit");
+                        ui.radio_value(&mut camera_mode, CameraMode::FreeFly, "Free fly");
+                        ui.radio_value(&mut camera_mode, CameraMode::Chase, "Chase");
+                        ui.radio_value(&mut camera_mode, CameraMode::FlyThrough, "Fly-through");
+                    });
+                    // Choosing the fly-through starts it over the current bookmarks, like
+                    // its Play button.
+                    if camera_mode == CameraMode::FlyThrough
+                        && cameras.get_mode() != CameraMode::FlyThrough
+                    {
+                        cameras.play_bookmarks();
+                    } else {
+                        cameras.set_mode(camera_mode);
+                    }
+                    match camera_mode {
+                        CameraMode::FreeFly => {
+                            Slider::new(&mut cameras.free_fly.speed, 0.1..=20.0)
+                                .logarithmic(true)
+                                .text("Camera speed")
+                                .ui(ui);
+                        }
+                        CameraMode::Chase => {
+                            Slider::new(&mut followed_duck, 0..=pool.duck_count - 1)
+                                .text("Followed duck")
+                                .ui(ui);
+                            Slider::new(&mut cameras.chase.distance, 0.2..=5.0)
+                                .step_by(0.05)
+                                .text("Chase distance")
+                                .ui(ui);
+                            Slider::new(&mut cameras.chase.height, 0.0..=3.0)
+                                .step_by(0.05)
+                                .text("Chase height")
+                                .ui(ui);
+                            Slider::new(&mut cameras.chase.smoothing, 0.5..=20.0)
+                                .logarithmic(true)
+                                .text("Chase smoothing")
+                                .ui(ui);
+                        }
+                        CameraMode::FlyThrough => {
+                            ui.add(egui::ProgressBar::new(cameras.fly_through.get_progress()));
+                        }
+                        CameraMode::Pose => {
+                            ui.label("Showing the replayed camera");
+                        }
+                        CameraMode::Orbit => {
+                            let orbit = &mut cameras.orbit;
+                            ui.horizontal(|ui| {
+                                ui.label("Orbit target:");
+                                DragValue::new(&mut orbit.target.x).speed(0.05).ui(ui);
+                                DragValue::new(&mut orbit.target.y).speed(0.05).ui(ui);
+                                DragValue::new(&mut orbit.target.z).speed(0.05).ui(ui);
+                            });
+                            let mut distance = orbit.get_distance();
+                            Slider::new(&mut distance, orbit.min_distance..=orbit.max_distance)
+                                .logarithmic(true)
+                                .text("Orbit distance")
+                                .ui(ui);
+                            orbit.set_distance(distance);
+                            Slider::new(&mut orbit.damping, 0.5..=30.0)
+                                .logarithmic(true)
+                                .text("Orbit damping")
+                                .ui(ui);
+                        }
+                    }
+
+                    ui.collapsing("Camera bookmarks", |ui| {
+                        ui.horizontal(|ui| {
+                            ui.text_edit_singleline(&mut bookmark_name);
+                            if ui.button("Add").clicked() {
+                                cameras.add_bookmark(bookmark_name.clone());
+                                bookmark_name = format!("Shot {}", cameras.bookmarks.len() + 1);
+                            }
+                        });
+
+                        let mut go_to = None;
+                        let mut remove = None;
+                        for (i, bookmark) in cameras.bookmarks.iter().enumerate() {
+                            ui.horizontal(|ui| {
+                                ui.label(&bookmark.name);
+                                if ui.button("Go to").clicked() {
+                                    go_to = Some(i);
+                                }
+                                if ui.button("Remove").clicked() {
+                                    remove = Some(i);
+                                }
+                            });
+                        }
+                        if let Some(i) = go_to {
+                            cameras.go_to_bookmark(i);
+                        }
+                        if let Some(i) = remove {
+                            cameras.bookmarks.remove(i);
+                        }
+
+                        Slider::new(&mut cameras.fly_through.duration, 1.0..=60.0)
+                            .text("Fly-through duration")
+                            .ui(ui);
+                        ui.checkbox(&mut cameras.fly_through.looping, "Loop");
+                        if ui.button("Play").clicked() {
+                            cameras.play_bookmarks();
+                        }
+
+                        ui.horizontal(|ui| {
+                            if ui.button("Save").clicked() {
+                                bookmarks_status =
+                                    match write_bookmarks(BOOKMARKS_FILE, &cameras.bookmarks) {
+                                        Ok(()) => format!("Saved to {}", BOOKMARKS_FILE),
+                                        Err(error) => format!("Save failed: {}", error),
+                                    };
+                            }
+                            if ui.button("Load").clicked() {
+                                bookmarks_status = match read_bookmarks(BOOKMARKS_FILE) {
+                                    Ok(bookmarks) => {
+                                        cameras.bookmarks = bookmarks;
+                                        format!("Loaded from {}", BOOKMARKS_FILE)
+                                    }
+                                    Err(error) => format!("Load failed: {}", error),
+                                };
+                            }
+                        });
+                        ui.label(&bookmarks_status);
+                    });
+
+                    ui.collapsing("Screenshot", |ui| {
+                        Slider::new(&mut screenshot_settings.supersampling, 1..=4)
+                            .text("Supersampling")
+                            .ui(ui);
+                        ui.checkbox(
+                            &mut screenshot_settings.include_overlay,
+                            "Include overlay (1x only)",
+                        );
+                        if ui.button("Take screenshot (F12)").clicked() {
+                            screenshot_requested = true;
+                        }
+                        ui.label(&screenshot_status);
+                    });
+
+                    ui.collapsing("Recording", |ui| {
+                        ui.add_enabled_ui(recorder.is_none(), |ui| {
+                            Slider::new(&mut recording_frame_rate, 10..=120)
+                                .text("Frame rate")
+                                .ui(ui);
+                            ui.checkbox(&mut recording_use_ffmpeg, "Encode video with ffmpeg");
+                        });
+                        let label = if recorder.is_some() {
+                            "Stop recording"
+                        } else {
+                            "Start recording"
+                        };
+                        if ui.button(label).clicked() {
+                            recording_toggle_requested = true;
+                        }
+                        if let Some(recorder) = &recorder {
+                            ui.label(format!(
+                                "Recording frame {} into {}{}",
+                                recorder.get_frame_count(),
+                                recorder.get_directory().display(),
+                                if recorder.is_encoding() {
+                                    " (with ffmpeg)"
+                                } else {
+                                    ""
+                                }
+                            ));
+                        }
+                        ui.label(&recording_status);
+                    });
+
+                    ui.collapsing("Input recording", |ui| {
+                        let label = if input_recording.is_some() {
+                            "Stop input recording"
+                        } else {
+                            "Start input recording"
+                        };
+                        if ui.button(label).clicked() {
+                            input_recording_toggle_requested = true;
+                        }
+                        if let Some(recording) = &input_recording {
+                            ui.label(format!("Recorded {} frames", recording.frames.len()));
+                        }
+                        if let Some(replay) = &replay {
+                            ui.label(format!("Replaying, {} frames left", replay.len()));
+                        }
+                        ui.label(&input_recording_status);
+                    });
+
+                    ui.collapsing("Rendering comparison", |ui| {
+                        ui.checkbox(&mut renderer.instanced_rendering, "Instanced rendering");
+                        ui.checkbox(&mut renderer.reflection_enabled, "Duck reflections");
+                        ui.checkbox(&mut renderer.benchmark_enabled, "Benchmark ducks");
+                        Slider::new(&mut renderer.benchmark_count, 1..=10000)
+                            .logarithmic(true)
+                            .text("Benchmark duck count")
+                            .ui(ui);
+                        ui.label(format!("Average FPS: {:.1}", average_fps));
+                    });
+                });
+            });
+
+            *control_flow = event_loop::ControlFlow::Poll;
+
+            steered_duck = steered_duck.min(pool.duck_count - 1);
+            followed_duck = followed_duck.min(pool.duck_count - 1);
+
+            if recording_toggle_requested {
+                recording_toggle_requested = false;
+                recording_status = match recorder.take() {
+                    Some(recorder) => match recorder.finish() {
+                        Ok(directory) => format!("Saved recording to {}", directory.display()),
+                        Err(error) => format!("Recording failed: {}", error),
+                    },
+                    None => {
+                        let size = window.inner_size();
+                        match Recorder::start(
+                            &display,
+                            &options.output_directory,
+                            size.width,
+                            size.height,
+                            recording_frame_rate,
+                            recording_use_ffmpeg,
+                        ) {
+                            Ok(started) => {
+                                recorder = Some(started);
+                                String::new()
+                            }
+                            Err(error) => format!("Recording failed: {}", error),
+                        }
+                    }
+                };
+            }
+
+            if input_recording_toggle_requested {
+                input_recording_toggle_requested = false;
+                input_recording_status = match input_recording.take() {
+                    Some(recording) => {
+                        let result =
+                            get_input_recording_path(&options.output_directory).and_then(|path| {
+                                write_input_recording(&path, &recording)?;
+                                Ok(path)
+                            });
+                        match result {
+                            Ok(path) => format!("Saved inputs to {}", path.display()),
+                            Err(error) => format!("Saving inputs failed: {}", error),
+                        }
+                    }
+                    None => match pool.restart(&display, &mut assets) {
+                        Ok(restarted) => {
+                            let size = window.inner_size();
+                            pool = restarted;
+                            input_recording = Some(InputRecording::new(
+                                pool.get_seed(),
+                                size.width,
+                                size.height,
+                                pool.get_grid_size(),
+                                options.scene_file.clone(),
+                            ));
+                            String::new()
+                        }
+                        Err(error) => format!("Recording failed: {}", error),
+                    },
+                };
+            }
+
+            if let Some(recording) = input_recording.as_mut() {
+                recording
+                    .frames
+                    .push(FrameInput::capture(frame_dt, &pool, &renderer, camera_pose));
+            }
+
+            pool.update(frame_dt);
+
+            if let Some(active_recorder) = recorder.as_mut() {
+                let frame = FrameContext::new(&pool, &cameras);
+                if let Err(error) = active_recorder.record(&display, &renderer, &frame) {
+                    recording_status = format!("Recording failed: {}", error);
+                    recorder = None;
+                }
+            }
+
+            let mut target = display.draw();
+
+            target.clear_color_and_depth((0.0, 0.0, 0.0, 1.0), 1.0);
+
+            renderer.render(
+                &display,
+                &mut RenderTarget::Frame(&mut target),
+                &FrameContext::new(&pool, &cameras),
+            );
+
+            egui_glium.paint(&display, &mut target);
+
+            target.finish().unwrap();
+
+            if screenshot_requested {
+                screenshot_requested = false;
+                let size = window.inner_size();
+                let result = get_screenshot_path(&options.output_directory).and_then(|path| {
+                    if screenshot_settings.reads_window() {
+                        save_window_screenshot(&display, &path)?;
+                    } else {
+                        let supersampling = capabilities.get_supersampling(
+                            size.width,
+                            size.height,
+                            screenshot_settings.supersampling,
+                        );
+                        save_offscreen_screenshot(
+                            &display,
+                            &renderer,
+                            &FrameContext::new(&pool, &cameras),
+                            size.width * supersampling,
+                            size.height * supersampling,
+                            &path,
+                        )?;
+                    }
+                    Ok(path)
+                });
+                screenshot_status = match result {
+                    Ok(path) => format!("Saved {}", path.display()),
+                    Err(error) => format!("Screenshot failed: {}", error),
+                };
+            }
+        };
+        match event {
+            event::Event::RedrawRequested(_) => redraw(),
+
+            event::Event::WindowEvent { event, .. } => {
+                use event::WindowEvent;
+                match &event {
+                    WindowEvent::CloseRequested | WindowEvent::Destroyed => {
+                        *control_flow = event_loop::ControlFlow::Exit;
+                    }
+                    WindowEvent::Resized(new_size) => {
+                        display.resize((*new_size).into());
+                        if replay.is_none() {
+                            cameras.resize(new_size.width, new_size.height);
+                        }
+                    }
+                    WindowEvent::CursorMoved { position, .. } => {
+                        let delta = (position.x - mouse_position.0, position.y - mouse_position.1);
+                        mouse_position = (position.x, position.y);
+                        if mouse_left_button_pressed {
+                            if let Some(hit) = pick_water(
+                                mouse_position,
+                                window.inner_size().into(),
+                                &cameras.get_perspective(),
+                                &cameras.get_view(),
+                                pool.water_height,
+                                pool.scale,
+                            ) {
+                                let now = Local::now();
+                                let strength = match previous_water_hit {
+                                    Some((previous_hit, previous_time)) => {
+                                        get_drag_strength(previous_hit, previous_time, hit, now)
+                                    }
+                                    None => 1.0,
+                                };
+                                pool.water_disturbances.push((hit, strength));
+                                previous_water_hit = Some((hit, now));
+                            }
+                        }
+                        if mouse_middle_button_pressed {
+                            cameras
+                                .get_active_mut()
+                                .rotate(Vector2::new(delta.0 as f32, delta.1 as f32));
+                        }
+                    }
+                    WindowEvent::MouseInput { state, button, .. }
+                        if *button == MouseButton::Middle =>
+                    {
+                        mouse_middle_button_pressed = *state == Pressed;
+                    }
+                    WindowEvent::MouseInput { state, button, .. }
+                        if *button == MouseButton::Left =>
+                    {
+                        let pressed =
+                            *state == Pressed && !egui_glium.egui_ctx.wants_pointer_input();
+                        mouse_left_button_pressed = pressed && !steering_enabled;
+                        previous_water_hit = None;
+                        if pressed {
+                            if let Some(hit) = pick_water(
+                                mouse_position,
+                                window.inner_size().into(),
+                                &cameras.get_perspective(),
+                                &cameras.get_view(),
+                                pool.water_height,
+                                pool.scale,
+                            ) {
+                                if steering_enabled {
+                                    pool.duck_destinations.push((steered_duck, hit));
+                                } else {
+                                    pool.water_disturbances.push((hit, 1.0));
+                                    previous_water_hit = Some((hit, Local::now()));
+                                }
+                            }
+                        }
+                    }
+                    WindowEvent::MouseWheel {
+                        delta: event::MouseScrollDelta::LineDelta(_x, y),
+                        ..
+                    } => {
+                        cameras.get_active_mut().zoom(*y);
+                    }
+                    WindowEvent::KeyboardInput {
+                        input:
+                            KeyboardInput {
+                                virtual_keycode: Some(key),
+                                state,
+                                ..
+                            },
+                        ..
+                    } => {
+                        let pressed =
+                            *state == Pressed && !egui_glium.egui_ctx.wants_keyboard_input();
+                        if pressed && *key == VirtualKeyCode::F12 {
+                            screenshot_requested = true;
+                        }
+                        cameras.get_active_mut().handle_key(*key, pressed);
+                    }
+                    _ => {}
+                }
+
+                let event_response = egui_glium.on_event(&event);
+
+                if event_response.repaint {
+                    window.request_redraw();
+                }
+            }
+            event::Event::NewEvents(event::StartCause::ResumeTimeReached { .. }) => {
+                window.request_redraw();
+            }
+            event::Event::MainEventsCleared => {
+                window.request_redraw();
+            }
+            _ => (),
+        }
+    });
+}
+
+fn get_drag_strength(
+    previous_hit: Point2<f32>,
+    previous_time: DateTime<Local>,
+    hit: Point2<f32>,
+    time: DateTime<Local>,
+) -> f32 {
+    let duration = time - previous_time;
+    let duration_in_seconds =
+        (duration.num_microseconds().unwrap_or(1) as f32 / 1_000_000.0).max(0.001);
+    let speed = (hit - previous_hit).norm() / duration_in_seconds;
+    clamp(speed * 0.1, 0.2, 2.0)
+}
@@ -0,0 +1,77 @@
+use std::path::Path;
+
+use duck::{read_scene, Options, Scene, USAGE};
+
+#[test]
+fn default_scene_is_valid() {
+    assert!(Scene::default().validate().is_empty());
+}
+
+#[test]
+fn example_scene_is_read_and_valid() {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("scenes/example.toml");
+    let scene = read_scene(&path).unwrap();
+    assert!(scene.validate().is_empty());
+}
+
+#[test]
+fn options_are_parsed() {
+    let args = ["duck", "--headless", "3", "--seed", "7", "--grid", "128"];
+    let options = Options::parse(args.map(String::from)).unwrap();
+    assert_eq!(options.headless_frames, Some(3));
+    assert_eq!(options.seed, 7);
+    assert_eq!(options.grid_size, Some(128));
+    assert!(USAGE.contains("--headless"));
+}
+
+#[test]
+fn reusable_modules_are_public() {
+    let names = [
+        std::any::type_name::<duck::water::water_simulation::WaterSimulation>(),
+        std::any::type_name::<duck::water::water_normal_computer::WaterNormalComputer>(),
+        std::any::type_name::<duck::meshes::mesh::Mesh>(),
+        std::any::type_name::<duck::meshes::vertex::Vertex>(),
+        std::any::type_name::<duck::cubes::cube_drawer::CubeDrawer>(),
+    ];
+    assert!(names.iter().all(|name| name.starts_with("duck::")));
+}
+
+#[test]
+fn render_passes_can_be_boxed() {
+    let passes: Vec<Box<dyn duck::RenderPass>> = vec![];
+    assert!(passes.is_empty());
+}
+
+/// Reads `content` as a scene file and returns the error.
+fn read_invalid_scene(name: &str, content: &str) -> String {
+    let path = std::env::temp_dir().join(format!("duck-{}-{}.toml", name, std::process::id()));
+    std::fs::write(&path, content).unwrap();
+    let result = read_scene(&path);
+    std::fs::remove_file(&path).unwrap();
+    result.err().unwrap()
+}
+
+#[test]
+fn unknown_scene_keys_are_reported() {
+    let error = read_invalid_scene("unknown", "[pool]\nscale = 5.0\nsize = 3.0\n");
+    assert!(error.contains("line 3, column 1"));
+    assert!(error.contains("unknown field `size`"));
+}
+
+#[test]
+fn mistyped_scene_values_are_reported() {
+    let error = read_invalid_scene("mistyped", "[water]\nrain = \"yes\"\n");
+    assert!(error.contains("line 2, column 8"));
+    assert!(error.contains("invalid type: string \"yes\", expected a boolean"));
+}
+
+#[test]
+fn out_of_range_scene_values_are_reported() {
+    let error = read_invalid_scene(
+        "range",
+        "[ducks]\ncount = 100\n\n[water]\nheight = 2.0\ngrid_size = 100\n",
+    );
+    assert!(error.contains("objects and ducks.count must add up to between 1 and 64"));
+    assert!(error.contains("water.height must be between -0.9 and 0.9"));
+    assert!(error.contains("water.grid_size must be a multiple of 8 between 64 and 2048"));
+}